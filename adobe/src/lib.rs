@@ -4,7 +4,6 @@ use after_effects as ae;
 use gyroflow_plugin_base::*;
 use gyroflow_plugin_base::gyroflow_core::GyroflowCoreError;
 use std::collections::HashSet;
-use lru::LruCache;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use ae::aegp::{ LayerFlags, LayerStream, TimeMode, PluginId, StreamValue };
@@ -15,6 +14,8 @@ mod premiere;
 mod parameters;
 use parameters::*;
 
+mod state;
+
 use serde::{ Serialize, Deserialize };
 
 static mut AEGP_PLUGIN_ID: PluginId = 0;
@@ -27,6 +28,15 @@ pub(crate) fn global_inst<'a>() -> &'a mut Plugin {
     }
 }
 
+/// Renders from the render queue, aerender or Media Encoder end up in a file, so they wait for the project to be loaded
+/// instead of passing the frames through
+pub(crate) fn is_final_render() -> bool {
+    if std::env::current_exe().is_ok_and(|x| x.to_string_lossy().contains("Media Encoder")) {
+        return true;
+    }
+    ae::aegp::suites::RenderQueue::new().and_then(|x| x.render_queue_state()).is_ok_and(|x| x == ae::aegp::RenderQueueState::Rendering)
+}
+
 #[derive(Default)]
 struct Plugin {
     gyroflow: GyroflowPluginBase
@@ -40,7 +50,6 @@ struct RenderData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct StoredParams {
-    pub version: u8,
     pub media_file_path: String,
     pub instance_id: String,
     pub sequence_size: (usize, usize),
@@ -58,7 +67,6 @@ impl Default for StoredParams {
     fn default() -> Self {
         let instance_id = format!("{}", fastrand::u64(..));
         Self {
-            version: 1,
             media_file_path: String::new(),
             instance_id: instance_id.clone(),
             sequence_size: (0, 0),
@@ -89,7 +97,14 @@ impl CrossThreadInstance {
     fn new_base_instance(instance_id: &mut String) -> GyroflowPluginBaseInstance {
         log::info!("new_base_instance: {:?}", instance_id);
         let mut gyroflow = GyroflowPluginBaseInstance {
-            managers:                       LruCache::new(std::num::NonZeroUsize::new(20).unwrap()),
+            managers:                       ManagerCache::for_instance("Instance"),
+            project_generation:             0,
+            background_loading:             !headless::is_headless(),
+            pending_load:                   None,
+            media_file_path:                String::new(),
+            ipc_generation:                 0,
+            unsaved_changes:                false,
+            preset_path:                    String::new(),
             original_output_size:           (0, 0),
             original_video_size:            (0, 0),
             timeline_size:                  (0, 0),
@@ -125,20 +140,25 @@ impl Default for Instance {
 }
 
 impl Instance {
-    fn stab_manager<'a, 'b>(&mut self, params: &'a mut ParamHandler<'a, 'b>, global: &mut Plugin, output_rect: ae::Rect) -> Option<Arc<StabilizationManager>> {
+    fn stab_manager<'a, 'b>(&mut self, params: &'a mut ParamHandler<'a, 'b>, global: &mut Plugin, output_rect: ae::Rect, clip: &ClipInfo) -> PluginResult<Arc<StabilizationManager>> {
         let out_size = (output_rect.width() as usize, output_rect.height() as usize);
 
-        self.gyroflow.as_mut().unwrap().stab_manager(params, &global.gyroflow.manager_cache, out_size, false).ok()
+        let gyroflow = self.gyroflow.as_mut().unwrap();
+        gyroflow.media_file_path = self.stored.read().media_file_path.clone();
+        gyroflow.set_interactive_render(!is_final_render());
+        let stab = gyroflow.stab_manager(params, &global.gyroflow.manager_cache, out_size, false)?;
+        gyroflow.update_status(params, &stab, clip);
+        Ok(stab)
+    }
+    fn pass_through_on_error(&self, error: &dyn std::error::Error) -> bool {
+        self.gyroflow.as_ref().is_some_and(|x| x.pass_through_on_error(error))
     }
 
     fn smart_render(plugin: &PluginState, extra: SmartRenderExtra, is_gpu: bool) -> Result<(), ae::Error> {
         let in_data = plugin.in_data;
         let cb = extra.callbacks();
+        // Empty while the project is loaded in the background, the input is passed through
         let stab = extra.pre_render_data::<RenderData>();
-        if stab.is_none() {
-            log::error!("empty stab data in smart_render");
-            return Ok(());
-        }
         let Some(mut input_world) = cb.checkout_layer_pixels(0)? else {
             return Ok(());
         };
@@ -240,6 +260,7 @@ impl Instance {
                         input:  BufferDescription { size: src_size,  rect: None, data: buffers.0, rotation: Some(input_rotation), texture_copy: buffers.2 },
                         output: BufferDescription { size: dest_size, rect: None, data: buffers.1, rotation: None, texture_copy: buffers.2 }
                     };
+                    ipc::report_frame(stab, timestamp_us);
                     if let Err(e) = match pixel_format {
                         ae::PixelFormat::GpuBgra128 |
                         ae::PixelFormat::Argb128    => stab.process_pixels::<RGBAf>(timestamp_us, None, &mut buffers),
@@ -247,7 +268,7 @@ impl Instance {
                         ae::PixelFormat::Argb32     => stab.process_pixels::<RGBA8>(timestamp_us, None, &mut buffers),
                         _ => Err(GyroflowCoreError::UnsupportedFormat(format!("{pixel_format:?}")))
                     } {
-                        log::error!("Failed to process pixels: {e:?}");
+                        PluginError::from(e).log();
                     }
                 } else {
                     output_world.copy_from(&input_world, None, None)?;
@@ -282,13 +303,14 @@ impl Instance {
                 input:  BufferDescription { size: src_size, rect: Some(src_rect), data: BufferSource::Cpu { buffer: src_buffer }, rotation: None, texture_copy: false },
                 output: BufferDescription { size: dst_size, rect: None,           data: BufferSource::Cpu { buffer: dst_buffer }, rotation: None, texture_copy: false }
             };
+            ipc::report_frame(stab, timestamp_us);
             if let Err(e) = match src.bit_depth() {
                 8  => stab.process_pixels::<RGBA8> (timestamp_us, None, &mut buffers),
                 16 => stab.process_pixels::<RGBA16>(timestamp_us, None, &mut buffers),
                 32 => stab.process_pixels::<RGBAf> (timestamp_us, None, &mut buffers),
                 bd => panic!("Unknown bit depth: {bd}")
             } {
-                log::error!("Failed to process pixels: {e:?}");
+                PluginError::from(e).log();
             }
         } else {
             dst.copy_from(src, None, None)?;
@@ -309,9 +331,7 @@ impl AdobePluginGlobal for Plugin {
             -1
         })?;
 
-        for param in GyroflowPluginBase::get_param_definitions() {
-            define_param(params, param, None);
-        }
+        define_params(params);
 
         // Save in global memory for use in Premiere GPU Filter entry
         param_index_for_type(Params::Fov, Some((*params.map).clone()));
@@ -372,6 +392,7 @@ impl AdobePluginGlobal for Plugin {
                 let _ = extra.dispatch::<ArbString, Params>(Params::ProjectData);
                 let _ = extra.dispatch::<ArbString, Params>(Params::EmbeddedLensProfile);
                 let _ = extra.dispatch::<ArbString, Params>(Params::EmbeddedPreset);
+                let _ = extra.dispatch::<ArbString, Params>(Params::ProjectOverrides);
                 let _ = extra.dispatch::<ArbString, Params>(Params::Status);
                 let _ = extra.dispatch::<ArbString, Params>(Params::ProjectPath);
             }
@@ -400,7 +421,10 @@ impl CrossThreadInstance {
     fn user_changed_param(&mut self, plugin: &mut PluginState, param: Params) -> Result<(), ae::Error> {
         match param {
             Params::Fov | Params::Smoothness | Params::ZoomLimit | Params::LensCorrectionStrength |
-            Params::HorizonLockAmount | Params::HorizonLockRoll |
+            Params::HorizonLockAmount | Params::HorizonLockRoll | Params::PositionX | Params::PositionY |
+            Params::ZoomMode | Params::ZoomSpeed | Params::ZoomMethod | Params::FrameReadoutTime | Params::ReadoutDirection | Params::SyncOffsetMs |
+            Params::SmoothingAlgorithm | Params::PerAxis | Params::SmoothnessPitch | Params::SmoothnessYaw | Params::SmoothnessRoll |
+            Params::MaxSmoothness | Params::MaxSmoothnessAtHighVelocity | Params::TimeConstant |
             Params::AdditionalPitch | Params::AdditionalYaw | Params::Rotation | Params::InputRotation | Params::VideoSpeed |
             Params::UseGyroflowsKeyframes | Params::RecalculateKeyframes |
             Params::OutputHeight | Params::OutputWidth | Params::OutputSizeSwap | Params::OutputSizeToTimeline => {
@@ -468,6 +492,7 @@ impl CrossThreadInstance {
         let stored = _self.stored.clone();
         let stored2 = _self.stored.clone();
         if let Some(inst) = _self.gyroflow.as_mut() {
+            inst.media_file_path = stored.read().media_file_path.clone();
             if param == Params::OutputSizeToTimeline && plugin.in_data.is_after_effects() {
                 let _ = (|| -> Result<(), ae::Error> {
                     let comp_size = plugin.in_data.effect()
@@ -592,24 +617,32 @@ impl CrossThreadInstance {
 
 impl AdobePluginInstance for CrossThreadInstance {
     fn flatten(&self) -> Result<(u16, Vec<u8>), Error> {
-        Ok((1, bincode::serde::encode_to_vec(&self, bincode::config::legacy()).unwrap()))
+        let _self = self.get().unwrap();
+        let _self = _self.read();
+        let bytes = state::encode_instance(&_self.stored.read(), _self.gyroflow.as_ref()).map_err(|e| {
+            log::error!("Failed to serialize instance: {e:?}");
+            Error::Generic
+        })?;
+        Ok((1, bytes))
     }
     fn unflatten(_version: u16, bytes: &[u8]) -> Result<Self, Error> {
-        match bincode::serde::decode_from_slice::<Self, _>(bytes, bincode::config::legacy()) {
-            Ok((inst, _)) => {
-                let mut _self = inst.get().unwrap();
-                let mut _self = _self.write();
-                if _self.gyroflow.is_none() {
-                    let gyroflow = {
-                        let mut stored = _self.stored.write();
-                        Self::new_base_instance(&mut stored.instance_id)
-                    };
-                    log::info!("_self.gyroflow is none: {} | instance_id: {}", _self.gyroflow.is_none(), _self.stored.read().instance_id);
+        match state::decode_instance(bytes) {
+            Ok((mut stored, gyroflow)) => {
+                let gyroflow = gyroflow.unwrap_or_else(|| {
+                    log::info!("gyroflow instance is none | instance_id: {}", stored.instance_id);
+                    Self::new_base_instance(&mut stored.instance_id)
+                });
+                let inst = Self::default();
+                {
+                    let _self = inst.get().unwrap();
+                    let mut _self = _self.write();
+                    *_self.stored.write() = stored;
                     _self.gyroflow = Some(gyroflow);
                 }
                 Ok(inst)
             },
-            Err(_) => {
+            Err(e) => {
+                log::error!("Failed to deserialize instance, using defaults: {e:?}");
                 Ok(Self::default())
             }
         }
@@ -672,7 +705,8 @@ impl AdobePluginInstance for CrossThreadInstance {
                 })();
 
                 if !footage_path.is_empty() {
-                    stored.pending_params_str.insert(Params::ProjectPath, GyroflowPluginBase::get_project_path(&footage_path).unwrap_or(footage_path.to_owned()));
+                    // Image sequences are reported as the path of their first frame
+                    stored.pending_params_str.insert(Params::ProjectPath, GyroflowPluginBase::get_project_path(&footage_path).unwrap_or_else(|| sequence::media_path(&footage_path)));
                     stored.media_file_path = footage_path;
                 } else if in_data.is_after_effects() {
                     plugin.out_data.set_return_msg("Unable to find the footage path.\nUse the \"Browse\" button and load the project file or a video file.");
@@ -735,21 +769,20 @@ impl AdobePluginInstance for CrossThreadInstance {
 
                     let full_rect = ae::Rect { left: 0, top: 0, right: w as _, bottom: h as _ };
 
-                    if let Some(stab) = _self.stab_manager(&mut params, plugin.global, full_rect) {
-                        {
-                            let duration_ms = stab.params.read().duration_ms;
-                            let old_range = stab.trim_ranges().first().cloned().unwrap_or((0.0, 1.0));
-                            let old_range_ms = ((old_range.0 * duration_ms).round() as i64, (old_range.1 * duration_ms).round() as i64);
-                            let new_range = trim_range.unwrap_or((0.0f64, duration_ms / 1000.0));
-                            let new_range_ms = ((new_range.0 * 1000.0).round() as i64, (new_range.1 * 1000.0).round() as i64);
-                            if old_range_ms != new_range_ms {
-                                log::info!("Trim range changed: {old_range_ms:?} != {new_range_ms:?}");
-
-                                stab.set_trim_ranges(vec![((new_range.0 * 1000.0) / duration_ms, (new_range.1 * 1000.0) / duration_ms)]);
-                                stab.invalidate_blocking_smoothing();
-                            }
-                        }
+                    let clip = ClipInfo {
+                        size:    Some((in_result.ref_width as usize, in_result.ref_height as usize)),
+                        trim_ms: trim_range.map(|(start, end)| (start * 1000.0, end * 1000.0)),
+                        ..Default::default()
+                    };
+                    let result = _self.stab_manager(&mut params, plugin.global, full_rect, &clip);
+                    if let Ok(stab) = result {
+                        trim::set_range(&stab, trim_range.map(|(start, end)| ((start * 1_000_000.0).round() as i64, (end * 1_000_000.0).round() as i64)));
                         extra.set_pre_render_data::<RenderData>(RenderData { stab, stored });
+                    } else if result.as_ref().is_err_and(|e| _self.pass_through_on_error(e.as_ref())) {
+                        // Pass-through on the CPU until the project is loaded, or after a failure in headless mode
+                        extra.set_gpu_render_possible(false);
+                        extra.set_result_rect(in_result.result_rect.into());
+                        extra.set_max_result_rect(in_result.max_result_rect.into());
                     } else {
                         extra.set_result_rect(ae::Rect::empty());
                         extra.set_max_result_rect(extra.result_rect());
@@ -774,10 +807,16 @@ impl AdobePluginInstance for CrossThreadInstance {
                 plugin.out_data.set_width(nw as _);
                 plugin.out_data.set_height(nh as _);
 
-                if let Some(stab) = _self.stab_manager(&mut params, plugin.global, out_layer.extent_hint()) {
-                    plugin.out_data.set_frame_data::<RenderData>(RenderData { stab, stored })
-                } else {
-                    log::error!("frame_setup: no stab manager");
+                let clip = ClipInfo { size: Some((in_data.width() as usize, in_data.height() as usize)), ..Default::default() };
+                match _self.stab_manager(&mut params, plugin.global, out_layer.extent_hint(), &clip) {
+                    Ok(stab) => plugin.out_data.set_frame_data::<RenderData>(RenderData { stab, stored }),
+                    Err(e) if _self.pass_through_on_error(e.as_ref()) => { }
+                    Err(e) => {
+                        log::error!("frame_setup: no stab manager: {e}");
+                        if headless::is_headless() {
+                            return Err(ae::Error::InvalidParms);
+                        }
+                    }
                 }
             }
             ae::Command::FrameSetdown => {
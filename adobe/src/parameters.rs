@@ -3,7 +3,6 @@ use gyroflow_plugin_base::*;
 use after_effects as ae;
 use premiere as pr;
 use ae::{ ParamFlag, ParamUIFlags, ValueDisplayFlag };
-use std::str::FromStr;
 use serde::{ Serialize, Serializer, Deserialize, Deserializer };
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -53,10 +52,34 @@ impl ae::ArbitraryData<ArbString> for ArbString {
     }
 }
 
-pub fn define_param(params: &mut ae::Parameters<Params>, x: ParameterType, _group: Option<&'static str>) {
-    match x {
-        ParameterType::HiddenString { id } => {
-            let p = Params::from_str(id).unwrap();
+/// Parameters added to the schema after the first release, by their `adobe_order`. After Effects and Premiere give
+/// the parameters their disk IDs in the order they are registered, and saved projects restore the values by these IDs
+pub fn added_later() -> Vec<&'static ParamDefinition> {
+    let mut list = ParamDefinition::all().iter().copied().filter(|x| x.adobe_order.is_some()).collect::<Vec<_>>();
+    list.sort_by_key(|x| x.adobe_order);
+    list
+}
+
+pub fn define_params(params: &mut ae::Parameters<Params>) {
+    for x in GyroflowPluginBase::get_param_definitions() {
+        define_param(params, x, None);
+    }
+    for x in added_later() {
+        add_param(params, x);
+    }
+}
+
+fn define_param(params: &mut ae::Parameters<Params>, x: &ParamDefinition, _group: Option<Params>) {
+    if x.adobe_order.is_some() { return; }
+    add_param(params, x);
+}
+
+fn add_param(params: &mut ae::Parameters<Params>, x: &ParamDefinition) {
+    let host = if params.in_data().is_after_effects() { Hosts::AFTER_EFFECTS } else { Hosts::PREMIERE };
+    if !x.is_available_in(host) { return; }
+    let (p, id, label) = (x.param, x.id, x.label);
+    match x.typ {
+        ParameterType::HiddenString => {
             params.add_customized(p, id, ae::ArbitraryDef::setup(|f| {
                 f.set_default::<ArbString>(ArbString::default()).unwrap();
             }), |param| {
@@ -65,8 +88,7 @@ pub fn define_param(params: &mut ae::Parameters<Params>, x: ParameterType, _grou
                 -1
             }).unwrap();
         }
-        ParameterType::TextBox { id, label, .. } => {
-            let p = Params::from_str(id).unwrap();
+        ParameterType::TextBox => {
             params.add_customized(p, label, ae::ArbitraryDef::setup(|f| {
                 f.set_default::<ArbString>(ArbString::default()).unwrap();
             }), |param| {
@@ -78,14 +100,10 @@ pub fn define_param(params: &mut ae::Parameters<Params>, x: ParameterType, _grou
             }).unwrap();
         }
 
-        ParameterType::Button { id, label, .. } => {
-            if id == "CreateCamera" && !params.in_data().is_after_effects() { return; }
-            let p = Params::from_str(id).unwrap();
-            if p == Params::LoadCurrent { return; }
+        ParameterType::Button => {
             params.add_with_flags(p, "", ae::ButtonDef::setup(|f| { f.set_label(label); }), ParamFlag::SUPERVISE | ParamFlag::CANNOT_TIME_VARY, ParamUIFlags::empty()).unwrap();
         }
-        ParameterType::Text { id, label, .. } => {
-            let p = Params::from_str(id).unwrap();
+        ParameterType::Text => {
             params.add_customized(p, label, ae::ArbitraryDef::setup(|f| {
                 f.set_default::<ArbString>(ArbString::default()).unwrap();
             }), |param| {
@@ -96,10 +114,7 @@ pub fn define_param(params: &mut ae::Parameters<Params>, x: ParameterType, _grou
                 -1
             }).unwrap();
         }
-        ParameterType::Slider { id, label, min, max, default, .. } => {
-            let p = Params::from_str(id).unwrap();
-            if p == Params::VideoSpeed { return; }
-            if p == Params::FusionStartFrame { return; }
+        ParameterType::Slider { min, max, default } => {
             params.add_with_flags(p, label, ae::FloatSliderDef::setup(|f| {
                 f.set_valid_min(min as f32);
                 f.set_slider_min(min as f32);
@@ -111,26 +126,25 @@ pub fn define_param(params: &mut ae::Parameters<Params>, x: ParameterType, _grou
                 f.set_display_flags(ValueDisplayFlag::NONE);
             }), ParamFlag::SUPERVISE, ParamUIFlags::empty()).unwrap();
         }
-        ParameterType::Checkbox { id, label, default, .. } => {
-            if id == "DontDrawOutside" { return; }
-            params.add_with_flags(Params::from_str(id).unwrap(), label, ae::CheckBoxDef::setup(|f| {
+        ParameterType::Checkbox { default } => {
+            params.add_with_flags(p, label, ae::CheckBoxDef::setup(|f| {
                 f.set_default(default);
                 f.set_value(default);
                 f.set_label("");
             }), ParamFlag::SUPERVISE, ParamUIFlags::empty()).unwrap();
         }
-        ParameterType::Select { id, label, options, default, .. } => {
-            params.add_with_flags(Params::from_str(id).unwrap(), label, ae::PopupDef::setup(|f| {
-                f.set_options(&options);
-                f.set_default(options.iter().position(|x| *x == default).unwrap_or(0) as i32);
+        ParameterType::Select { .. } | ParameterType::DynamicSelect { .. } => {
+            let options = x.options();
+            params.add_with_flags(p, label, ae::PopupDef::setup(|f| {
+                f.set_options(&options.iter().map(String::as_str).collect::<Vec<_>>());
+                f.set_default(x.default_index());
                 f.set_value(f.default());
             }), ParamFlag::SUPERVISE, ParamUIFlags::empty()).unwrap();
         }
-        ParameterType::Group { id, label, parameters, opened } => {
-            if id == "InfoGroup" { return; }
-            params.add_group(Params::from_str(id).unwrap(), Params::from_str(&format!("{id}End")).unwrap(), label, !opened, |params| {
+        ParameterType::Group { end, parameters, opened } => {
+            params.add_group(p, end, label, !opened, |params| {
                 for x in parameters {
-                    define_param(params, x, Some(id));
+                    define_param(params, x, Some(p));
                 }
                 Ok(())
             }).unwrap();
@@ -467,3 +481,30 @@ impl<'a, 'b> GyroflowPluginParams for ParamHandler<'a, 'b> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parameters of the first release, registered in the order of the schema. Never add to this list
+    const FIRST_RELEASE: &[Params] = &[
+        Params::InstanceId, Params::ProjectPath, Params::ProjectData, Params::EmbeddedLensProfile, Params::EmbeddedPreset,
+        Params::ProjectGroup, Params::Status, Params::Browse, Params::LoadLens, Params::OpenGyroflow, Params::ReloadProject, Params::OpenRecentProject,
+        Params::AdjustGroup, Params::Smoothness, Params::ZoomLimit, Params::LensCorrectionStrength, Params::HorizonLockAmount, Params::HorizonLockRoll,
+        Params::AdditionalPitch, Params::AdditionalYaw, Params::Rotation, Params::InputRotation, Params::Fov, Params::DisableStretch, Params::IntegrationMethod,
+        Params::KeyframesGroup, Params::UseGyroflowsKeyframes, Params::StabilizationSpeedRamp, Params::RecalculateKeyframes, Params::CreateCamera,
+        Params::OutputSizeGroup, Params::OutputWidth, Params::OutputHeight, Params::OutputSizeToTimeline, Params::OutputSizeSwap, Params::Interpolation,
+        Params::ToggleOverview, Params::IncludeProjectData, Params::LoadedProject, Params::LoadedPreset, Params::LoadedLens,
+    ];
+
+    #[test]
+    fn new_parameters_have_an_adobe_order() {
+        for x in ParamDefinition::all() {
+            if !x.is_available_in(Hosts::AFTER_EFFECTS) && !x.is_available_in(Hosts::PREMIERE) { continue; }
+            assert!(FIRST_RELEASE.contains(&x.param) != x.adobe_order.is_some(), "{:?} needs the next adobe_order", x.param);
+        }
+        // Unique and without gaps
+        let orders = added_later().iter().map(|x| x.adobe_order.unwrap()).collect::<Vec<_>>();
+        assert_eq!(orders, (1..=orders.len() as u16).collect::<Vec<_>>());
+    }
+}
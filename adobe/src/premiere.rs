@@ -47,7 +47,9 @@ impl pr::GpuFilter for PremiereGPU {
                 {
                     let keyframe_test = [ Params::Fov, Params::Smoothness, Params::ZoomLimit, Params::LensCorrectionStrength,
                                           Params::HorizonLockAmount, Params::HorizonLockRoll, Params::VideoSpeed, Params::Rotation,
-                                          Params::AdditionalYaw, Params::AdditionalPitch ];
+                                          Params::AdditionalYaw, Params::AdditionalPitch, Params::SmoothnessPitch, Params::SmoothnessYaw,
+                                          Params::SmoothnessRoll, Params::MaxSmoothness, Params::MaxSmoothnessAtHighVelocity, Params::TimeConstant,
+                                          Params::PositionX, Params::PositionY, Params::SyncOffsetMs ];
                     let mut stored = inst.stored.write();
                     stored.premiere_keyframed_params.clear();
                     for kf in keyframe_test {
@@ -62,7 +64,7 @@ impl pr::GpuFilter for PremiereGPU {
                 if inst.stored.read().media_file_path.is_empty() {
                     if let Ok(pr::PropertyData::String(media_path)) = filter.video_segment_suite.node_property(media_node.1, pr::Property::Media_InstanceString) {
                         let mut stored = inst.stored.write();
-                        stored.pending_params_str.insert(Params::ProjectPath, GyroflowPluginBase::get_project_path(&media_path).unwrap_or(media_path.to_owned()));
+                        stored.pending_params_str.insert(Params::ProjectPath, GyroflowPluginBase::get_project_path(&media_path).unwrap_or_else(|| sequence::media_path(&media_path)));
                         stored.media_file_path = media_path;
                         stored.sequence_size = (render_params.render_width() as _, render_params.render_height() as _);
                     }
@@ -172,10 +174,21 @@ impl pr::GpuFilter for PremiereGPU {
 
                 // let time_interpolation_type = if let Ok(pr::PropertyData::Int32(num)) = filter.video_segment_suite.node_property(media_node.1, pr::Property::Media_StreamTimeInterpolationType) { num } else { 0 };
 
+                let clip = ClipInfo {
+                    fps:  Some(inst.stored.read().media_fps_ticks).filter(|x| *x > 0).map(|x| ticks_per_sec / x as f64),
+                    size: Some((in_size.0 as usize, in_size.1 as usize)),
+                    ..Default::default()
+                };
+
+                let media_file_path = inst.stored.read().media_file_path.clone();
                 let base_inst = inst.gyroflow.as_mut().unwrap();
+                base_inst.media_file_path = media_file_path;
                 base_inst.timeline_size = (render_params.render_width() as _, render_params.render_height() as _);
+                base_inst.set_interactive_render(!super::is_final_render());
 
-                if let Ok(stab) = base_inst.stab_manager(&mut params, &super::global_inst().gyroflow.manager_cache, (out_size.0 as _, out_size.1 as _), false) {
+                let result = base_inst.stab_manager(&mut params, &super::global_inst().gyroflow.manager_cache, (out_size.0 as _, out_size.1 as _), false);
+                if let Ok(stab) = result {
+                    base_inst.update_status(&mut params, &stab, &clip);
                     /*{
                         let duration_ms = stab.params.read().duration_ms;
                         let old_range = stab.trim_ranges().first().cloned().unwrap_or((0.0, 1.0));
@@ -238,15 +251,21 @@ impl pr::GpuFilter for PremiereGPU {
                         input:  BufferDescription { size: src_size,  rect: None,           data: buffers.0, rotation: Some(input_rotation), texture_copy: buffers.2 },
                         output: BufferDescription { size: dest_size, rect: Some(out_rect), data: buffers.1, rotation: None,                 texture_copy: buffers.2 }
                     };
+                    ipc::report_frame(&stab, timestamp_us);
                     if let Err(e) = match pixel_format {
                         pr::PixelFormat::GpuBgra4444_32f => stab.process_pixels::<RGBAf>(timestamp_us, None, &mut buffers),
                         pr::PixelFormat::GpuBgra4444_16f => stab.process_pixels::<RGBAf16>(timestamp_us, None, &mut buffers),
                         _ => Err(GyroflowCoreError::UnsupportedFormat(format!("{pixel_format:?}")))
                     } {
-                        log::error!("Failed to process pixels: {e:?}");
+                        PluginError::from(e).log();
+                    }
+                } else if let Err(e) = result {
+                    if base_inst.is_loading() || headless::is_headless() {
+                        // GPU frames can't be passed through here, fall back to the software render, which fails or passes the frame through
+                        return Err(pr::Error::NotImplemented);
+                    } else {
+                        log::error!("Key not found: {key}: {e}");
                     }
-                } else {
-                    log::error!("Key not found: {key}");
                 }
             }
         }
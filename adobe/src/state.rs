@@ -0,0 +1,185 @@
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::state::*;
+use serde::{ Serialize, Deserialize };
+use std::collections::{ HashMap, HashSet };
+use crate::StoredParams;
+
+/// `StoredParams` as saved by every release before the state was versioned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredParamsV1 {
+    pub version: u8,
+    pub media_file_path: String,
+    pub instance_id: String,
+    pub sequence_size: (usize, usize),
+    pub media_fps: f64,
+    pub media_fps_ticks: i64,
+    pub pending_params_f64: HashMap<Params, f64>,
+    pub pending_params_bool: HashMap<Params, bool>,
+    pub pending_params_str: HashMap<Params, String>,
+    pub pending_params_i32: HashMap<Params, i32>,
+    pub premiere_keyframed_params: HashSet<Params>,
+    pub speed_per_frame: Vec<f64>,
+    pub speed_checksum: u64
+}
+impl From<StoredParamsV1> for StoredParams {
+    fn from(v: StoredParamsV1) -> Self {
+        // `version` was never used, the layout version is now stored next to the data
+        Self {
+            media_file_path:           v.media_file_path,
+            instance_id:               v.instance_id,
+            sequence_size:             v.sequence_size,
+            media_fps:                 v.media_fps,
+            media_fps_ticks:           v.media_fps_ticks,
+            pending_params_f64:        v.pending_params_f64,
+            pending_params_bool:       v.pending_params_bool,
+            pending_params_str:        v.pending_params_str,
+            pending_params_i32:        v.pending_params_i32,
+            premiere_keyframed_params: v.premiere_keyframed_params,
+            speed_per_frame:           v.speed_per_frame,
+            speed_checksum:            v.speed_checksum,
+        }
+    }
+}
+
+impl VersionedState for StoredParams {
+    const VERSION: u16 = 2;
+
+    fn upgrade(version: u16, data: &[u8]) -> PluginResult<Self> {
+        match version {
+            1 => Ok(decode::<StoredParamsV1>(data)?.into()),
+            2 => decode(data),
+            _ => Err(format!("Unknown layout version of StoredParams: {version}").into())
+        }
+    }
+}
+
+pub fn encode_instance(stored: &StoredParams, gyroflow: Option<&GyroflowPluginBaseInstance>) -> PluginResult<Vec<u8>> {
+    let blobs = (stored.to_blob()?, gyroflow.map(|x| x.to_blob()).transpose()?);
+    let mut ret = STATE_MAGIC.to_vec();
+    ret.extend(encode(&blobs)?);
+    Ok(ret)
+}
+
+pub fn decode_instance(bytes: &[u8]) -> PluginResult<(StoredParams, Option<GyroflowPluginBaseInstance>)> {
+    match bytes.strip_prefix(STATE_MAGIC) {
+        Some(data) => {
+            let (stored, gyroflow): (VersionedBlob, Option<VersionedBlob>) = decode(data)?;
+            Ok((StoredParams::from_blob(&stored)?, gyroflow.as_ref().map(GyroflowPluginBaseInstance::from_blob).transpose()?))
+        },
+        None => {
+            // Saved before the state was versioned, this is the layout of `Instance` in these releases
+            let (stored, gyroflow): (StoredParamsV1, Option<GyroflowPluginBaseInstanceV1>) = decode(bytes)?;
+            Ok((stored.into(), gyroflow.map(Into::into)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(lines: &[&str]) -> Vec<u8> {
+        let s = lines.concat();
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    // `Instance` in the unversioned layout, with one pending value of each type
+    const V1_INSTANCE: &[&str] = &[
+        "011300000000000000433a2f666f6f746167652f636c69702e6d70340a0000000000000031323334353637383930000f",
+        "0000000000007008000000000000b81e85eb51f83d40006b30f901000000010000000000000012000000000000000000",
+        "e03f0100000000000000280000000101000000000000000e00000002000000000000004f4b0100000000000000320000",
+        "00020000000100000000000000110000000200000000000000000000000000f03f000000000000e03f2a000000000000",
+        "000100000f00000000000070080000000000008007000000000000380400000000000080070000000000003804000000",
+        "0000002c01000000000000b81e85eb51f83d4001010100000100",
+    ];
+    // Same as above, saved before the base instance was created
+    const V1_INSTANCE_NO_BASE: &[&str] = &[
+        "011300000000000000433a2f666f6f746167652f636c69702e6d70340a0000000000000031323334353637383930000f",
+        "0000000000007008000000000000b81e85eb51f83d40006b30f901000000010000000000000012000000000000000000",
+        "e03f0100000000000000280000000101000000000000000e00000002000000000000004f4b0100000000000000320000",
+        "00020000000100000000000000110000000200000000000000000000000000f03f000000000000e03f2a000000000000",
+        "0000",
+    ];
+
+    fn check_stored(stored: &StoredParams) {
+        assert_eq!(stored.media_file_path, "C:/footage/clip.mp4");
+        assert_eq!(stored.instance_id, "1234567890");
+        assert_eq!(stored.sequence_size, (3840, 2160));
+        assert_eq!(stored.media_fps, 29.97);
+        assert_eq!(stored.media_fps_ticks, 8475667200);
+        assert_eq!(stored.pending_params_f64, HashMap::from([(Params::Smoothness, 0.5)]));
+        assert_eq!(stored.pending_params_bool, HashMap::from([(Params::ToggleOverview, true)]));
+        assert_eq!(stored.pending_params_str, HashMap::from([(Params::Status, "OK".to_owned())]));
+        assert_eq!(stored.pending_params_i32, HashMap::from([(Params::Interpolation, 2)]));
+        assert_eq!(stored.premiere_keyframed_params, HashSet::from([Params::Fov]));
+        assert_eq!(stored.speed_per_frame, vec![1.0, 0.5]);
+        assert_eq!(stored.speed_checksum, 42);
+    }
+    fn check_base(base: &GyroflowPluginBaseInstance) {
+        assert!(!base.reload_values_from_project);
+        assert_eq!(base.original_video_size, (3840, 2160));
+        assert_eq!(base.original_output_size, (1920, 1080));
+        assert_eq!(base.timeline_size, (1920, 1080));
+        assert_eq!(base.num_frames, 300);
+        assert_eq!(base.fps, 29.97);
+        assert!(base.has_motion);
+        assert!(base.ever_changed);
+        assert!(base.cache_keyframes_every_frame);
+        assert!(!base.framebuffer_inverted);
+        assert!(!base.anamorphic_adjust_size);
+        assert!(base.always_set_input_rotation);
+        assert!(!base.opencl_disabled);
+    }
+
+    #[test]
+    fn unversioned_instance_is_upgraded() {
+        let (stored, base) = decode_instance(&hex(V1_INSTANCE)).unwrap();
+        check_stored(&stored);
+        check_base(&base.expect("base instance should be decoded"));
+    }
+
+    #[test]
+    fn unversioned_instance_without_base() {
+        let (stored, base) = decode_instance(&hex(V1_INSTANCE_NO_BASE)).unwrap();
+        check_stored(&stored);
+        assert!(base.is_none());
+    }
+
+    #[test]
+    fn current_layout_round_trip() {
+        let (stored, base) = decode_instance(&hex(V1_INSTANCE)).unwrap();
+        let bytes = encode_instance(&stored, base.as_ref()).unwrap();
+        assert!(bytes.starts_with(STATE_MAGIC));
+
+        let (stored, base) = decode_instance(&bytes).unwrap();
+        check_stored(&stored);
+        check_base(&base.unwrap());
+    }
+
+    #[test]
+    fn versioned_v1_stored_params_are_upgraded() {
+        let (v1, _): (StoredParamsV1, Option<GyroflowPluginBaseInstanceV1>) = decode(&hex(V1_INSTANCE)).unwrap();
+        let blobs = (VersionedBlob { version: 1, data: encode(&v1).unwrap() }, None::<VersionedBlob>);
+        let mut bytes = STATE_MAGIC.to_vec();
+        bytes.extend(encode(&blobs).unwrap());
+
+        let (stored, base) = decode_instance(&bytes).unwrap();
+        check_stored(&stored);
+        assert!(base.is_none());
+    }
+
+    #[test]
+    fn newer_layout_is_an_error() {
+        let blobs = (VersionedBlob { version: StoredParams::VERSION + 1, data: Vec::new() }, None::<VersionedBlob>);
+        let mut bytes = STATE_MAGIC.to_vec();
+        bytes.extend(encode(&blobs).unwrap());
+
+        assert!(decode_instance(&bytes).is_err());
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let bytes = hex(V1_INSTANCE);
+        assert!(decode_instance(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
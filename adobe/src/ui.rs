@@ -73,7 +73,7 @@ pub fn draw(_in_data: &ae::InData, params: &mut ae::Parameters<Params>, event: &
                 let font = supplier.new_default_font(supplier.default_font_size()? * 0.9)?;
                 let text_color = if status == "OK" {
                     ae::drawbot::ColorRgba { red: 0.22, green: 0.86, blue: 0.1, alpha: 1.0 } // Green
-                } else if status == "Calculating..." {
+                } else if status == "Calculating..." || status.starts_with("Loading...") {
                     ae::drawbot::ColorRgba { red: 0.92, green: 0.57, blue: 0.08, alpha: 1.0 } // Yellow
                 } else if status == "---" {
                     ae::drawbot::ColorRgba { red: 0.8, green: 0.8, blue: 0.8, alpha: 1.0 } // White
@@ -0,0 +1,151 @@
+// Cache of loaded `StabilizationManager`s, limited by their estimated memory usage instead of the number of entries.
+// A few 4K high frame rate projects with dense gyro data can take gigabytes, while many small projects take almost nothing.
+// Every instance also keeps the managers it used, which stay alive after the global cache evicted them,
+// so instance caches only get a small share of the budget.
+
+use lru::LruCache;
+use std::sync::Arc;
+use gyroflow_core::StabilizationManager;
+
+/// Environment variable with the cache budget in megabytes, takes precedence over the setting
+pub const BUDGET_ENV_VAR: &str = "GYROFLOW_PLUGIN_CACHE_MB";
+/// Gyroflow setting with the cache budget in megabytes
+pub const BUDGET_SETTING: &str = "pluginCacheBudgetMB";
+pub const DEFAULT_BUDGET_MB: u64 = 2048;
+/// Share of the budget for the cache of each instance
+pub const INSTANCE_BUDGET_DIVISOR: usize = 32;
+
+// Key, value and tree node overhead of one quaternion in a `BTreeMap<i64, Quat64>`
+const QUATERNION_SIZE: usize = 8 + 32 + 16;
+
+/// Budget from the environment variable or the gyroflow settings, in bytes
+pub fn budget_bytes() -> usize {
+    let mb = std::env::var(BUDGET_ENV_VAR).ok()
+        .and_then(|x| x.trim().parse::<u64>().ok())
+        .or_else(|| gyroflow_core::settings::try_get(BUDGET_SETTING).and_then(|x| x.as_u64().or_else(|| x.as_str()?.trim().parse().ok())))
+        .unwrap_or(DEFAULT_BUDGET_MB);
+    (mb as usize).saturating_mul(1024 * 1024)
+}
+
+/// Budget of the cache of each instance, in bytes
+pub fn instance_budget_bytes() -> usize {
+    budget_bytes() / INSTANCE_BUDGET_DIVISOR
+}
+
+/// Estimated memory used by the manager: gyro samples and quaternions.
+/// The frame buffers belong to the host and aren't counted
+pub fn estimated_size(stab: &StabilizationManager) -> usize {
+    let gyro = stab.gyro.read();
+    let md = gyro.file_metadata.read();
+
+    let samples = std::mem::size_of_val(&md.raw_imu[..]);
+    let quaternions = (md.quaternions.len() + gyro.quaternions.len() + gyro.smoothed_quaternions.len()) * QUATERNION_SIZE;
+
+    std::mem::size_of::<StabilizationManager>() + samples + quaternions
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    stab: Arc<StabilizationManager>,
+    size: usize,
+}
+
+#[derive(Clone)]
+pub struct ManagerCache {
+    name: &'static str,
+    entries: LruCache<String, CacheEntry>,
+    budget: usize,
+    used: usize,
+    stats: CacheStats,
+}
+
+impl ManagerCache {
+    pub fn new(name: &'static str, budget: usize) -> Self {
+        Self { name, entries: LruCache::unbounded(), budget, used: 0, stats: CacheStats::default() }
+    }
+    /// Cache with the budget from `budget_bytes`
+    pub fn with_default_budget(name: &'static str) -> Self {
+        Self::new(name, budget_bytes())
+    }
+    /// Cache with the budget from `instance_budget_bytes`
+    pub fn for_instance(name: &'static str) -> Self {
+        Self::new(name, instance_budget_bytes())
+    }
+
+    /// Returns the manager and measures it again, because smoothing and syncing change its size after it was inserted
+    pub fn get(&mut self, key: &str) -> Option<&Arc<StabilizationManager>> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            log::info!("{} manager cache miss: {key} | {}", self.name, self.summary());
+            return None;
+        };
+        self.stats.hits += 1;
+        let size = estimated_size(&entry.stab);
+        self.used = self.used - entry.size + size;
+        entry.size = size;
+        self.evict();
+        self.entries.peek(key).map(|x| &x.stab)
+    }
+    /// Same as `get`, without updating the order and the statistics
+    pub fn peek(&self, key: &str) -> Option<&Arc<StabilizationManager>> {
+        self.entries.peek(key).map(|x| &x.stab)
+    }
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains(key)
+    }
+    pub fn peek_lru(&self) -> Option<(&String, &Arc<StabilizationManager>)> {
+        self.entries.peek_lru().map(|(k, v)| (k, &v.stab))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Arc<StabilizationManager>)> {
+        self.entries.iter().map(|(k, v)| (k, &v.stab))
+    }
+
+    /// Inserts the manager and evicts the least recently used ones until the cache fits in the budget.
+    /// The newest entry is always kept, even if it alone exceeds the budget
+    pub fn put(&mut self, key: String, stab: Arc<StabilizationManager>) {
+        let size = estimated_size(&stab);
+        if let Some(old) = self.entries.put(key, CacheEntry { stab, size }) {
+            self.used -= old.size;
+        }
+        self.used += size;
+        self.evict();
+    }
+    /// Evicts the least recently used managers until the cache fits in the budget, keeping the most recent one
+    fn evict(&mut self) {
+        while self.used > self.budget && self.entries.len() > 1 {
+            let Some((key, entry)) = self.entries.pop_lru() else { break; };
+            self.used -= entry.size;
+            self.stats.evictions += 1;
+            log::info!("{} manager cache evicted: {key} ({:.1} MB) | {}", self.name, entry.size as f64 / 1024.0 / 1024.0, self.summary());
+        }
+    }
+    pub fn pop(&mut self, key: &str) -> Option<Arc<StabilizationManager>> {
+        let entry = self.entries.pop(key)?;
+        self.used -= entry.size;
+        Some(entry.stab)
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used = 0;
+    }
+
+    pub fn len(&self) -> usize { self.entries.len() }
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+    /// Estimated size of all cached managers, in bytes
+    pub fn used_bytes(&self) -> usize { self.used }
+    pub fn budget_bytes(&self) -> usize { self.budget }
+    pub fn stats(&self) -> CacheStats { self.stats }
+
+    fn summary(&self) -> String {
+        format!("{} entries, {:.1}/{:.1} MB, hits: {}, misses: {}, evictions: {}",
+            self.entries.len(), self.used as f64 / 1024.0 / 1024.0, self.budget as f64 / 1024.0 / 1024.0,
+            self.stats.hits, self.stats.misses, self.stats.evictions)
+    }
+}
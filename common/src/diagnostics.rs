@@ -0,0 +1,71 @@
+// Checks run after a `StabilizationManager` is loaded, to warn about clips which won't be stabilized correctly.
+// Every host passes what it knows about the clip, and the most severe problem is displayed in `Params::Status`.
+
+use gyroflow_core::StabilizationManager;
+use crate::PluginError;
+
+/// What the host knows about the clip the effect is applied to
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClipInfo {
+    /// Frame rate at which the host delivers the frames
+    pub fps: Option<f64>,
+    /// Size of the source frames
+    pub size: Option<(usize, usize)>,
+    /// Used range of the source media, in milliseconds
+    pub trim_ms: Option<(f64, f64)>,
+}
+
+/// Returns all problems found, most severe first
+pub fn check(stab: &StabilizationManager, clip: &ClipInfo) -> Vec<PluginError> {
+    let mut ret = Vec::new();
+
+    let params = stab.params.read();
+    let gyro = stab.gyro.read();
+    let md = gyro.file_metadata.read();
+
+    // Range of the gyro data, in milliseconds
+    let gyro_range = if let (Some(first), Some(last)) = (md.raw_imu.first(), md.raw_imu.last()) {
+        Some((first.timestamp_ms, last.timestamp_ms))
+    } else if let (Some((first, _)), Some((last, _))) = (md.quaternions.first_key_value(), md.quaternions.last_key_value()) {
+        Some((*first as f64 / 1000.0, *last as f64 / 1000.0))
+    } else {
+        None
+    };
+
+    if gyro_range.is_none() {
+        ret.push(PluginError::GyroDataMissing);
+    } else if !md.has_accurate_timestamps && gyro.get_offsets().is_empty() {
+        ret.push(PluginError::GyroNotSynced);
+    }
+
+    if let Some(fps) = clip.fps {
+        if (fps - params.fps).abs() > 0.01 {
+            ret.push(PluginError::FrameRateMismatch { clip_fps: params.fps, timeline_fps: fps });
+        }
+    }
+
+    if stab.lens.read().fisheye_params.camera_matrix.is_empty() {
+        ret.push(PluginError::LensProfileMissing);
+    }
+
+    // Different resolution with the same aspect ratio is fine (eg. proxies), the frame is scaled
+    if let Some(size) = clip.size {
+        if size.0 > 0 && size.1 > 0 && params.size.0 > 0 && params.size.1 > 0 {
+            let clip_ratio  = size.0 as f64 / size.1 as f64;
+            let video_ratio = params.size.0 as f64 / params.size.1 as f64;
+            if (clip_ratio / video_ratio - 1.0).abs() > 0.01 {
+                ret.push(PluginError::AspectMismatch { video: params.size, clip: size });
+            }
+        }
+    }
+
+    if let (Some(trim), Some(gyro_range)) = (clip.trim_ms, gyro_range) {
+        let frame_ms = 1000.0 / params.fps.max(1.0);
+        if trim.0 < gyro_range.0 - frame_ms || trim.1 > gyro_range.1 + frame_ms {
+            ret.push(PluginError::TrimOutsideGyroRange { trim_ms: trim, gyro_ms: gyro_range });
+        }
+    }
+
+    ret.sort_by_key(|x| std::cmp::Reverse(x.severity()));
+    ret
+}
@@ -0,0 +1,51 @@
+// Encoding of the project data, lens profile and preset stored in the host's project file.
+// A project with gyro data can take many megabytes of JSON, so it's stored zlib compressed and base64 encoded,
+// with a prefix to tell it apart from the plain JSON saved by older versions.
+
+use std::io::{ Read, Write };
+use base64::Engine;
+use crate::{ GyroflowPluginParams, Params, PluginResult };
+
+pub const MAGIC: &str = "gfz1:";
+
+/// Parameters with the embedded data
+pub const PARAMS: &[Params] = &[Params::ProjectData, Params::EmbeddedLensProfile, Params::EmbeddedPreset];
+
+pub fn is_encoded(value: &str) -> bool {
+    value.starts_with(MAGIC)
+}
+
+pub fn encode(data: &str) -> String {
+    if data.is_empty() || is_encoded(data) {
+        return data.to_owned();
+    }
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    let compressed = encoder.write_all(data.as_bytes()).and_then(|_| encoder.finish());
+    match compressed {
+        Ok(compressed) => format!("{MAGIC}{}", base64::engine::general_purpose::STANDARD.encode(compressed)),
+        Err(e) => {
+            log::error!("Failed to compress embedded data: {e:?}");
+            data.to_owned()
+        }
+    }
+}
+
+/// Accepts both the encoded and the plain data
+pub fn decode(value: &str) -> std::io::Result<String> {
+    let Some(encoded) = value.strip_prefix(MAGIC) else { return Ok(value.to_owned()); };
+    let compressed = base64::engine::general_purpose::STANDARD.decode(encoded.trim())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut data = String::new();
+    flate2::read::ZlibDecoder::new(&compressed[..]).read_to_string(&mut data)?;
+    Ok(data)
+}
+
+/// Reads the decoded value of one of `PARAMS`
+pub fn get(params: &dyn GyroflowPluginParams, param: Params) -> PluginResult<String> {
+    Ok(decode(&params.get_string(param)?)?)
+}
+
+/// Stores the encoded value in one of `PARAMS`
+pub fn set(params: &mut dyn GyroflowPluginParams, param: Params, data: &str) -> PluginResult<()> {
+    params.set_string(param, &encode(data))
+}
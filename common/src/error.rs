@@ -0,0 +1,115 @@
+// Failures reported to the user through the `Status` parameter.
+// Every host converts errors to the same status text, hint and severity, so a failure looks the same in every editor.
+
+use gyroflow_core::GyroflowCoreError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginError {
+    /// No project or video file selected
+    NoProject,
+    ProjectNotFound(String),
+    ProjectInvalid { path: String, reason: String },
+    VideoUnreadable { path: String, reason: String },
+    LensProfileInvalid(String),
+    PresetInvalid(String),
+    GyroNotSynced,
+    FrameRateMismatch { clip_fps: f64, timeline_fps: f64 },
+    UnsupportedPixelFormat(String),
+    Gpu(String),
+    GyroDataMissing,
+    LensProfileMissing,
+    AspectMismatch { video: (usize, usize), clip: (usize, usize) },
+    TrimOutsideGyroRange { trim_ms: (f64, f64), gyro_ms: (f64, f64) },
+    /// Project is being loaded in the background
+    Loading,
+}
+
+impl PluginError {
+    /// Short text displayed in `Params::Status`
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::NoProject                 => "Project not loaded",
+            Self::ProjectNotFound(_)        => "Project file not found!",
+            Self::ProjectInvalid { .. }     => "Failed to load project!",
+            Self::VideoUnreadable { .. }    => "Failed to load file info!",
+            Self::LensProfileInvalid(_)     => "Invalid lens profile!",
+            Self::PresetInvalid(_)          => "Invalid preset!",
+            Self::GyroNotSynced             => "Not synced. Open in Gyroflow",
+            Self::FrameRateMismatch { .. }  => "Timeline fps mismatch!",
+            Self::UnsupportedPixelFormat(_) => "Unsupported pixel format!",
+            Self::Gpu(_)                    => "Rendering failed!",
+            Self::GyroDataMissing           => "No gyro data!",
+            Self::LensProfileMissing        => "No lens profile!",
+            Self::AspectMismatch { .. }     => "Aspect ratio mismatch!",
+            Self::TrimOutsideGyroRange { .. } => "Trim outside gyro data!",
+            Self::Loading                   => "Loading...",
+        }
+    }
+
+    /// Longer explanation, displayed as the tooltip of `Params::Status`
+    pub fn hint(&self) -> String {
+        match self {
+            Self::NoProject                        => "Select a .gyroflow project or a video file with embedded gyro data".into(),
+            Self::ProjectNotFound(path)            => format!("File {path} doesn't exist or is not accessible."),
+            Self::ProjectInvalid { path, reason }  => format!("Error loading {path}: {reason}."),
+            Self::VideoUnreadable { path, reason } => format!("Error loading {path}: {reason}."),
+            Self::LensProfileInvalid(reason)       => format!("Failed to load lens profile: {reason}"),
+            Self::PresetInvalid(reason)            => format!("Failed to load preset: {reason}"),
+            Self::GyroNotSynced                    => "Gyro data is not synced with the video, open the video in Gyroflow and add sync points (eg. by doing autosync)".into(),
+            Self::FrameRateMismatch { clip_fps, timeline_fps } => format!("Timeline frame rate ({timeline_fps:.3}) doesn't match the clip frame rate ({clip_fps:.3})! Use the plugin in Fusion instead"),
+            Self::UnsupportedPixelFormat(format)   => format!("Pixel format {format} is not supported, change the project bit depth or color format"),
+            Self::Gpu(reason)                      => format!("Failed to process the frame: {reason}"),
+            Self::GyroDataMissing                  => "The video doesn't contain gyro data. Open it in Gyroflow, load the gyro data and save the project".into(),
+            Self::LensProfileMissing               => "Lens profile is not loaded, the lens distortion will not be corrected. Select a lens profile in Gyroflow or use the \"Load lens\" button".into(),
+            Self::AspectMismatch { video, clip }   => format!("Clip size ({}x{}) has a different aspect ratio than the video in the project ({}x{}), the frame will be cropped", clip.0, clip.1, video.0, video.1),
+            Self::TrimOutsideGyroRange { trim_ms, gyro_ms } => format!("Used range of the clip ({:.2}s - {:.2}s) is outside of the gyro data ({:.2}s - {:.2}s), these frames won't be stabilized", trim_ms.0 / 1000.0, trim_ms.1 / 1000.0, gyro_ms.0 / 1000.0, gyro_ms.1 / 1000.0),
+            Self::Loading                          => "Project is being loaded, frames are passed through until it's ready".into(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::NoProject |
+            Self::Loading => Severity::Info,
+            Self::GyroNotSynced |
+            Self::FrameRateMismatch { .. } |
+            Self::LensProfileInvalid(_) |
+            Self::PresetInvalid(_) |
+            Self::LensProfileMissing |
+            Self::AspectMismatch { .. } |
+            Self::TrimOutsideGyroRange { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn log(&self) {
+        match self.severity() {
+            Severity::Info    => log::info!("{}: {}", self.status(), self.hint()),
+            Severity::Warning => log::warn!("{}: {}", self.status(), self.hint()),
+            Severity::Error   => log::error!("{}: {}", self.status(), self.hint()),
+        }
+    }
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.status(), self.hint())
+    }
+}
+impl std::error::Error for PluginError { }
+
+impl From<GyroflowCoreError> for PluginError {
+    fn from(e: GyroflowCoreError) -> Self {
+        match e {
+            GyroflowCoreError::UnsupportedFormat(format) => Self::UnsupportedPixelFormat(format),
+            e => Self::Gpu(e.to_string()),
+        }
+    }
+}
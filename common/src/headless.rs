@@ -0,0 +1,50 @@
+// Headless mode for render nodes and automated pipelines, where nobody can answer a dialog.
+// In this mode the plugins never open windows or start the Gyroflow app, projects are loaded before the frame is rendered,
+// and a project which fails to load either fails the render or passes the frames through, as configured.
+
+use std::sync::OnceLock;
+
+/// Environment variable enabling the headless mode: `fail` (or `1`) or `passthrough`
+pub const HEADLESS_ENV_VAR: &str = "GYROFLOW_PLUGIN_HEADLESS";
+/// Gyroflow setting with the same values as the environment variable, used when the variable isn't set
+pub const HEADLESS_SETTING: &str = "pluginHeadless";
+
+/// What to render when the project can't be loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Report the render as failed
+    Fail,
+    /// Render the source frame unchanged
+    PassThrough,
+}
+
+fn parse(value: &str) -> Option<FailureMode> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "fail"               => Some(FailureMode::Fail),
+        "passthrough" | "pass-through" | "pass"     => Some(FailureMode::PassThrough),
+        _ => None
+    }
+}
+
+/// `None` when the plugins run interactively
+pub fn failure_mode() -> Option<FailureMode> {
+    static MODE: OnceLock<Option<FailureMode>> = OnceLock::new();
+    *MODE.get_or_init(|| {
+        let mode = match std::env::var(HEADLESS_ENV_VAR) {
+            Ok(v) => parse(&v),
+            Err(_) => match gyroflow_core::settings::try_get(HEADLESS_SETTING) {
+                Some(serde_json::Value::Bool(true)) => Some(FailureMode::Fail),
+                Some(serde_json::Value::String(v))  => parse(&v),
+                _ => None
+            }
+        };
+        if let Some(mode) = mode {
+            log::info!("Running in headless mode, on load failure: {mode:?}");
+        }
+        mode
+    })
+}
+
+pub fn is_headless() -> bool {
+    failure_mode().is_some()
+}
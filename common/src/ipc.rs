@@ -0,0 +1,426 @@
+// Live link between the plugin and a running Gyroflow app, over a local channel which doesn't need network access.
+// On Unix every plugin process listens on a socket in `ipc_dir()`. Elsewhere, or when the socket can't be created,
+// the messages are exchanged as files in a mailbox directory of the process, in the same place.
+// The app pushes parameter and sync point changes for a project path, and the plugin sends back the frame it renders.
+// Messages are JSON, one per line on the socket or one per file in the mailbox.
+
+use std::collections::{ BTreeMap, HashMap };
+use std::io::{ self, BufRead, Write };
+use std::path::{ Path, PathBuf };
+use std::sync::{ Arc, OnceLock, Weak, mpsc };
+use std::sync::atomic::{ AtomicBool, AtomicUsize, Ordering::SeqCst };
+use std::time::{ Duration, Instant };
+use parking_lot::Mutex;
+use gyroflow_core::StabilizationManager;
+use crate::{ ParamDefinition, ParameterType, Params };
+
+/// Environment variable enabling the link: `1` or `0`, takes precedence over the setting
+pub const IPC_ENV_VAR: &str = "GYROFLOW_PLUGIN_IPC";
+/// Gyroflow setting enabling the link
+pub const IPC_SETTING: &str = "pluginLiveLink";
+/// Environment variable with the directory of the sockets and mailboxes
+pub const IPC_DIR_ENV_VAR: &str = "GYROFLOW_PLUGIN_IPC_DIR";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// An app which doesn't read its socket for this long is disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// From the app: new values of the plugin parameters, in the units of the parameters. Only keyframable sliders are applied
+    Params { project: String, values: BTreeMap<Params, f64> },
+    /// From the app: all sync points of the project, as timestamp in microseconds and offset in milliseconds
+    SyncPoints { project: String, offsets: Vec<(i64, f64)> },
+    /// From the plugin: the frame rendered by the editor
+    CurrentFrame { project: String, timestamp_us: i64 },
+}
+impl Message {
+    pub fn project(&self) -> &str {
+        match self {
+            Self::Params { project, .. } | Self::SyncPoints { project, .. } | Self::CurrentFrame { project, .. } => project
+        }
+    }
+}
+
+pub fn ipc_dir() -> PathBuf {
+    match std::env::var(IPC_DIR_ENV_VAR) {
+        Ok(v) if !v.is_empty() => PathBuf::from(v),
+        _ => std::env::temp_dir().join("gyroflow-plugin-ipc")
+    }
+}
+
+/// The link is opt-in, and never enabled in headless mode
+pub fn is_enabled() -> bool {
+    if crate::headless::is_headless() { return false; }
+    match std::env::var(IPC_ENV_VAR) {
+        Ok(v) => matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => gyroflow_core::settings::try_get(IPC_SETTING).and_then(|x| x.as_bool()).unwrap_or_default()
+    }
+}
+
+fn same_project(a: &str, b: &str) -> bool {
+    let normalize = |x: &str| x.trim().replace('\\', "/");
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        normalize(a).eq_ignore_ascii_case(&normalize(b))
+    } else {
+        normalize(a) == normalize(b)
+    }
+}
+
+fn to_line(msg: &Message) -> io::Result<String> {
+    serde_json::to_string(msg).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+fn from_line(line: &str) -> Option<Message> {
+    match serde_json::from_str(line.trim()) {
+        Ok(msg) => Some(msg),
+        Err(e) => { log::warn!("Invalid IPC message: {e:?}: {line}"); None }
+    }
+}
+
+// ---------------------------------------- Mailbox ----------------------------------------
+
+/// Directory where each message is a file, written atomically and removed by the reader
+struct Mailbox(PathBuf);
+impl Mailbox {
+    fn put(&self, msg: &Message) -> io::Result<()> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let name = format!("{nanos:024}-{:08}", COUNTER.fetch_add(1, SeqCst));
+        let tmp = self.0.join(format!("{name}.tmp"));
+        std::fs::write(&tmp, to_line(msg)?)?;
+        std::fs::rename(tmp, self.0.join(format!("{name}.json")))
+    }
+    fn take(&self) -> Vec<Message> {
+        let Ok(entries) = std::fs::read_dir(&self.0) else { return Vec::new(); };
+        let mut files = entries.flatten().map(|x| x.path()).filter(|x| x.extension().is_some_and(|e| e == "json")).collect::<Vec<_>>();
+        files.sort();
+        files.into_iter().filter_map(|file| {
+            let data = std::fs::read_to_string(&file).ok();
+            let _ = std::fs::remove_file(&file);
+            from_line(&data?)
+        }).collect()
+    }
+}
+
+// ---------------------------------------- Plugin side ----------------------------------------
+
+enum Channel {
+    #[cfg(unix)]
+    Socket(Arc<Mutex<Vec<std::os::unix::net::UnixStream>>>),
+    Files { outbox: Mailbox },
+}
+impl Channel {
+    fn send(&self, msg: &Message) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Channel::Socket(streams) => {
+                let line = format!("{}\n", to_line(msg)?);
+                // Disconnected apps, and the ones which stopped reading, are dropped
+                streams.lock().retain_mut(|x| x.write_all(line.as_bytes()).is_ok());
+                Ok(())
+            },
+            Channel::Files { outbox } => outbox.put(msg)
+        }
+    }
+}
+
+/// Plugin side of the link. Received messages are passed to the callback from a background thread,
+/// and sent messages are written from another one, so a slow app never blocks the render thread
+pub struct Link {
+    path: PathBuf,
+    channel: Arc<Channel>,
+    outgoing: mpsc::Sender<Message>,
+    stop: Arc<AtomicBool>,
+}
+
+fn link_name() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    format!("plugin-{}-{}", std::process::id(), COUNTER.fetch_add(1, SeqCst))
+}
+
+impl Link {
+    fn new(path: PathBuf, channel: Channel, stop: Arc<AtomicBool>) -> Self {
+        let channel = Arc::new(channel);
+        let (outgoing, rx) = mpsc::channel::<Message>();
+        {
+            let channel = channel.clone();
+            // Ends when the link is dropped
+            std::thread::spawn(move || {
+                for msg in rx {
+                    if let Err(e) = channel.send(&msg) {
+                        log::warn!("Failed to send the IPC message: {e:?}");
+                    }
+                }
+            });
+        }
+        Self { path, channel, outgoing, stop }
+    }
+
+    /// Listens on a socket, or in a mailbox when sockets aren't available
+    pub fn listen(dir: &Path, on_message: impl Fn(Message) + Send + Sync + 'static) -> io::Result<Self> {
+        let on_message = Arc::new(on_message);
+        #[cfg(unix)]
+        {
+            let cb = on_message.clone();
+            match Self::listen_socket(dir, move |x| cb(x)) {
+                Ok(link) => return Ok(link),
+                Err(e) => log::warn!("Unable to create the IPC socket in {dir:?}, using files instead: {e:?}")
+            }
+        }
+        Self::listen_files(dir, move |x| on_message(x))
+    }
+
+    #[cfg(unix)]
+    pub fn listen_socket(dir: &Path, on_message: impl Fn(Message) + Send + Sync + 'static) -> io::Result<Self> {
+        use std::os::unix::net::UnixListener;
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.sock", link_name()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let streams = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let on_message = Arc::new(on_message);
+        {
+            let (streams, stop) = (streams.clone(), stop.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(SeqCst) { break; }
+                    let Ok(stream) = stream else { continue; };
+                    if let Ok(writer) = stream.try_clone() {
+                        let _ = writer.set_write_timeout(Some(WRITE_TIMEOUT));
+                        streams.lock().push(writer);
+                    }
+                    let on_message = on_message.clone();
+                    std::thread::spawn(move || {
+                        for line in io::BufReader::new(stream).lines() {
+                            let Ok(line) = line else { break; };
+                            if let Some(msg) = from_line(&line) {
+                                on_message(msg);
+                            }
+                        }
+                    });
+                }
+            });
+        }
+        log::info!("Listening for Gyroflow on {path:?}");
+        Ok(Self::new(path, Channel::Socket(streams), stop))
+    }
+
+    pub fn listen_files(dir: &Path, on_message: impl Fn(Message) + Send + Sync + 'static) -> io::Result<Self> {
+        let path = dir.join(link_name());
+        let (inbox, outbox) = (Mailbox(path.join("in")), Mailbox(path.join("out")));
+        std::fs::create_dir_all(&inbox.0)?;
+        std::fs::create_dir_all(&outbox.0)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                while !stop.load(SeqCst) {
+                    for msg in inbox.take() {
+                        on_message(msg);
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            });
+        }
+        log::info!("Listening for Gyroflow in {path:?}");
+        Ok(Self::new(path, Channel::Files { outbox }, stop))
+    }
+
+    /// Socket file or mailbox directory, which the app connects to
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Queues the message for every connected app, without waiting for it to be written
+    pub fn send(&self, msg: &Message) -> io::Result<()> {
+        self.outgoing.send(msg.clone()).map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "IPC sender thread stopped"))
+    }
+}
+impl Drop for Link {
+    fn drop(&mut self) {
+        self.stop.store(true, SeqCst);
+        match &*self.channel {
+            #[cfg(unix)]
+            Channel::Socket(streams) => {
+                for x in streams.lock().drain(..) {
+                    let _ = x.shutdown(std::net::Shutdown::Both);
+                }
+                // Wake up the accepting thread, so it can see the stop flag
+                let _ = std::os::unix::net::UnixStream::connect(&self.path);
+                let _ = std::fs::remove_file(&self.path);
+            },
+            Channel::Files { .. } => {
+                let _ = std::fs::remove_dir_all(&self.path);
+            }
+        }
+    }
+}
+
+// ---------------------------------------- App side ----------------------------------------
+
+/// App side of the link. Stands in for Gyroflow in tests
+pub struct Peer {
+    #[cfg(unix)]
+    stream: Option<io::BufReader<std::os::unix::net::UnixStream>>,
+    mailboxes: Option<(Mailbox, Mailbox)>,
+}
+impl Peer {
+    /// Links of all plugin processes in `dir`
+    pub fn discover(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new(); };
+        let mut links = entries.flatten().map(|x| x.path())
+            .filter(|x| x.file_name().is_some_and(|x| x.to_string_lossy().starts_with("plugin-")))
+            .collect::<Vec<_>>();
+        links.sort();
+        links
+    }
+
+    pub fn connect(link: &Path) -> io::Result<Self> {
+        if link.is_dir() {
+            return Ok(Self {
+                #[cfg(unix)]
+                stream: None,
+                mailboxes: Some((Mailbox(link.join("in")), Mailbox(link.join("out")))),
+            });
+        }
+        #[cfg(unix)]
+        return Ok(Self { stream: Some(io::BufReader::new(std::os::unix::net::UnixStream::connect(link)?)), mailboxes: None });
+        #[cfg(not(unix))]
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "Sockets are not supported"));
+    }
+
+    pub fn send(&mut self, msg: &Message) -> io::Result<()> {
+        if let Some((inbox, _)) = &self.mailboxes {
+            return inbox.put(msg);
+        }
+        #[cfg(unix)]
+        if let Some(stream) = &mut self.stream {
+            return stream.get_mut().write_all(format!("{}\n", to_line(msg)?).as_bytes());
+        }
+        Err(io::ErrorKind::NotConnected.into())
+    }
+
+    /// Next message from the plugin, `None` after the timeout
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Message> {
+        if let Some((_, outbox)) = &self.mailboxes {
+            let start = Instant::now();
+            loop {
+                if let Some(msg) = outbox.take().into_iter().next() {
+                    return Some(msg);
+                }
+                if start.elapsed() > timeout { return None; }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+        #[cfg(unix)]
+        if let Some(stream) = &mut self.stream {
+            stream.get_ref().set_read_timeout(Some(timeout)).ok()?;
+            let mut line = String::new();
+            return match stream.read_line(&mut line) {
+                Ok(n) if n > 0 => from_line(&line),
+                _ => None
+            };
+        }
+        None
+    }
+}
+
+// ---------------------------------------- Plugin state ----------------------------------------
+
+#[derive(Default)]
+struct State {
+    link: Option<Link>,
+    /// Managers created for each project, to apply the sync points and find the project of a rendered frame
+    managers: Vec<(Weak<StabilizationManager>, String)>,
+    /// Latest parameter values from the app for each project, with the number of updates
+    params: HashMap<String, (u64, BTreeMap<Params, f64>)>,
+    last_frames: HashMap<String, i64>,
+}
+fn state() -> &'static Mutex<State> {
+    static STATE: OnceLock<Mutex<State>> = OnceLock::new();
+    STATE.get_or_init(Default::default)
+}
+
+/// Parameters the app can change: the adjustment values, which are also stored in the project
+fn is_adjustable(param: Params) -> bool {
+    ParamDefinition::find(param).is_some_and(|x| x.keyframable && matches!(x.typ, ParameterType::Slider { .. }))
+}
+
+fn handle(msg: Message) {
+    let mut state = state().lock();
+    match msg {
+        Message::Params { project, mut values } => {
+            values.retain(|param, value| {
+                let valid = is_adjustable(*param) && value.is_finite();
+                if !valid { log::warn!("Ignoring the value {value} of {param:?} from the app"); }
+                valid
+            });
+            if values.is_empty() { return; }
+            let entry = state.params.entry(project).or_default();
+            entry.0 += 1;
+            entry.1.extend(values);
+        },
+        Message::SyncPoints { project, offsets } => {
+            let managers = state.managers.iter().filter(|x| same_project(&x.1, &project)).filter_map(|x| x.0.upgrade()).collect::<Vec<_>>();
+            drop(state);
+            log::info!("Received {} sync points for {project}", offsets.len());
+            for stab in managers {
+                // The plugin's sync adjustment stays on top of the new offsets
+                crate::sync_offset::set_project_offsets(&stab, offsets.iter().copied().collect());
+                stab.invalidate_blocking_smoothing();
+                stab.invalidate_blocking_zooming();
+            }
+        },
+        Message::CurrentFrame { .. } => { }
+    }
+}
+
+/// Starts listening in `dir`, if the link isn't running yet. Returns the path the app can connect to
+pub fn start(dir: &Path) -> io::Result<PathBuf> {
+    let mut state = state().lock();
+    if let Some(link) = &state.link {
+        return Ok(link.path().to_path_buf());
+    }
+    let link = Link::listen(dir, handle)?;
+    let path = link.path().to_path_buf();
+    state.link = Some(link);
+    Ok(path)
+}
+
+fn is_running() -> bool {
+    state().lock().link.is_some()
+}
+
+/// Links the manager with the project path, starting the link if it's enabled
+pub fn register(project: &str, stab: &Arc<StabilizationManager>) {
+    if !is_running() {
+        static STARTED: AtomicBool = AtomicBool::new(false);
+        if !is_enabled() || STARTED.swap(true, SeqCst) { return; }
+        if let Err(e) = start(&ipc_dir()) {
+            log::error!("Unable to start the Gyroflow live link: {e:?}");
+            return;
+        }
+    }
+    let mut state = state().lock();
+    state.managers.retain(|x| x.0.strong_count() > 0);
+    state.managers.push((Arc::downgrade(stab), project.to_owned()));
+}
+
+/// Parameter values received for the project after `generation`, with the new generation
+pub fn params_update(project: &str, generation: u64) -> Option<(u64, BTreeMap<Params, f64>)> {
+    let state = state().lock();
+    state.params.iter().find(|x| same_project(x.0, project)).map(|x| x.1).filter(|x| x.0 > generation).cloned()
+}
+
+/// Sends the timestamp of the rendered frame to the app, if it changed.
+/// Called from the render thread, so the message is only queued, outside of the state lock
+pub fn report_frame(stab: &Arc<StabilizationManager>, timestamp_us: i64) {
+    let mut state = state().lock();
+    let Some(outgoing) = state.link.as_ref().map(|x| x.outgoing.clone()) else { return; };
+    let Some(project) = state.managers.iter().find(|x| std::ptr::eq(x.0.as_ptr(), Arc::as_ptr(stab))).map(|x| x.1.clone()) else { return; };
+    if state.last_frames.insert(project.clone(), timestamp_us) == Some(timestamp_us) { return; }
+    drop(state);
+    if outgoing.send(Message::CurrentFrame { project, timestamp_us }).is_err() {
+        log::warn!("Failed to send the current frame, the IPC sender thread stopped");
+    }
+}
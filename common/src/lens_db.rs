@@ -0,0 +1,54 @@
+// Lens profile database shared by every `StabilizationManager` created by the plugins.
+// It's loaded once per process, on the first use, and reloaded when the user's `lens_profiles` directory changes.
+
+use std::path::PathBuf;
+use std::sync::{ Arc, OnceLock };
+use parking_lot::RwLock;
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::lens_profile_database::LensProfileDatabase;
+use crate::watcher;
+
+fn db() -> &'static Arc<RwLock<LensProfileDatabase>> {
+    static DB: OnceLock<Arc<RwLock<LensProfileDatabase>>> = OnceLock::new();
+    DB.get_or_init(|| {
+        watcher::watch_directory(&user_profiles_dir(), reload);
+        Default::default()
+    })
+}
+
+/// Directory with the lens profiles created or downloaded by the user
+pub fn user_profiles_dir() -> PathBuf {
+    gyroflow_core::settings::data_dir().join("lens_profiles")
+}
+
+/// The shared database, it may not be loaded yet, see `ensure_loaded`
+pub fn shared() -> Arc<RwLock<LensProfileDatabase>> {
+    db().clone()
+}
+
+/// Makes `stab` use the shared database
+pub fn share_with(stab: &mut StabilizationManager) {
+    stab.lens_profile_db = shared();
+}
+
+/// Loads the database if it wasn't loaded yet. Loading takes a while, so it should be called from a background thread
+pub fn ensure_loaded() {
+    let db = db();
+    if db.read().loaded { return; }
+
+    let mut db = db.write();
+    // Another thread could have loaded it while we were waiting for the lock
+    if !db.loaded {
+        let time = std::time::Instant::now();
+        db.load_all();
+        log::info!("Loaded lens profile database in {:.2}ms", time.elapsed().as_micros() as f64 / 1000.0);
+    }
+}
+
+/// Loads the database from scratch and replaces the shared one, managers which use it see the new profiles
+pub fn reload() {
+    log::info!("Lens profiles changed, reloading the database");
+    let mut new_db = LensProfileDatabase::default();
+    new_db.load_all();
+    *db().write() = new_db;
+}
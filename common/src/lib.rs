@@ -1,10 +1,10 @@
 
-use lru::LruCache;
 use parking_lot::{ Mutex, RwLock };
 use std::cell::Cell;
-use std::sync::{ Arc, atomic::AtomicBool };
+use std::sync::Arc;
 
 pub use gyroflow_core::{ StabilizationManager, keyframes::*, stabilization::*, filesystem, gpu::* };
+pub use gyroflow_core::stabilization_params::ReadoutDirection;
 pub use gyroflow_core;
 
 // re-exports
@@ -12,6 +12,34 @@ pub use rfd;
 pub use parking_lot;
 pub use lru;
 
+pub mod cache;
+pub mod diagnostics;
+pub mod embedded;
+pub mod error;
+pub mod headless;
+pub mod ipc;
+pub mod lens_db;
+pub mod loader;
+pub mod memory_params;
+pub mod notification;
+pub mod overrides;
+pub mod path_remap;
+pub mod presets;
+pub mod project_export;
+pub mod schema;
+pub mod sequence;
+pub mod sidecar;
+pub mod smoothing;
+pub mod state;
+pub mod sync_offset;
+pub mod trim;
+pub mod watcher;
+pub use schema::*;
+pub use error::*;
+pub use diagnostics::ClipInfo;
+pub use cache::ManagerCache;
+pub use notification::{ Notification, notify };
+
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
@@ -40,8 +68,6 @@ pub enum Params {
     LensCorrectionStrength,
     HorizonLockAmount,
     HorizonLockRoll,
-    // PositionX,
-    // PositionY,
     AdditionalPitch,
     AdditionalYaw,
     InputRotation,
@@ -68,6 +94,30 @@ pub enum Params {
     CreateCamera,
     Interpolation,
     FusionStartFrame,
+    TimestampScale,
+    WatchProjectFiles,
+    ExtractProjectData,
+    LoadPreset,
+    SavePreset,
+    SmoothingAlgorithm,
+    PerAxis,
+    SmoothnessPitch,
+    SmoothnessYaw,
+    SmoothnessRoll,
+    MaxSmoothness,
+    MaxSmoothnessAtHighVelocity,
+    TimeConstant,
+    PositionX,
+    PositionY,
+    ZoomMode,
+    ZoomSpeed,
+    ZoomMethod,
+    FrameReadoutTime,
+    ReadoutDirection,
+    SyncOffsetMs,
+    TrimStart,
+    TrimEnd,
+    ProjectOverrides,
 }
 
 thread_local! {
@@ -78,14 +128,14 @@ pub struct GyroflowPluginBase {
     // We should cache managers globally because it's common to have the effect applied to the same clip and cut the clip into multiple pieces
     // We don't want to create a new manager for each piece of the same clip
     // Cache key is specific enough
-    pub manager_cache: Mutex<LruCache<String, Arc<StabilizationManager>>>,
+    pub manager_cache: Mutex<ManagerCache>,
 
     pub context_initialized: bool,
 }
 impl Default for GyroflowPluginBase {
     fn default() -> Self {
         Self {
-            manager_cache: Mutex::new(LruCache::new(std::num::NonZeroUsize::new(8).unwrap())),
+            manager_cache: Mutex::new(ManagerCache::with_default_budget("Global")),
             context_initialized: false,
         }
     }
@@ -104,6 +154,17 @@ impl GyroflowPluginBase {
         }
         data.to_string()
     }
+    /// Removes the flag added by `maybe_inject_disable_stretch`, returns the data and whether the flag was set
+    fn take_disable_stretch(data: &str) -> (String, bool) {
+        if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(data) {
+            if let Some(flag) = json.as_object_mut().and_then(|x| x.remove("plugin_disable_stretch")) {
+                if let Ok(s) = serde_json::to_string_pretty(&json) {
+                    return (s, flag.as_bool().unwrap_or_default());
+                }
+            }
+        }
+        (data.to_string(), false)
+    }
 
     pub fn initialize_gpu_context(&mut self) {
         log::info!("GyroflowPluginBase::initialize_gpu_context");
@@ -171,30 +232,15 @@ impl GyroflowPluginBase {
     }
 
     pub fn get_project_path(file_path: &str) -> Option<String> {
-        let mut project_path = std::path::Path::new(file_path).with_extension("gyroflow");
-        if !project_path.exists() {
-            // Find first project path that begins with the file name
-            if let Some(parent) = project_path.parent() {
-                if let Ok(paths) = std::fs::read_dir(parent) {
-                    if let Some(fname) = project_path.with_extension("").file_name().map(|x| x.to_string_lossy().to_string()) {
-                        for path in paths {
-                            if let Ok(path) = path {
-                                let path_fname = path.file_name().to_string_lossy().to_string();
-                                if path_fname.starts_with(&fname) && path_fname.ends_with(".gyroflow") {
-                                    project_path = path.path();
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        if project_path.exists() {
-            Some(project_path.to_string_lossy().to_string())
-        } else {
-            None
+        Self::get_project_paths(file_path).into_iter().next()
+    }
+    /// All projects found for the video file, best match first. See `sidecar::find_projects`
+    pub fn get_project_paths(file_path: &str) -> Vec<String> {
+        let paths = sidecar::find_projects(file_path).into_iter().map(|x| x.path.to_string_lossy().to_string()).collect::<Vec<_>>();
+        if paths.len() > 1 {
+            log::info!("Found {} projects for {file_path}: {paths:?}", paths.len());
         }
+        paths
     }
 
     pub fn get_gyroflow_location() -> Option<String> {
@@ -213,6 +259,10 @@ impl GyroflowPluginBase {
     }
 
     pub fn open_gyroflow(project_path: Option<&str>) {
+        if headless::is_headless() {
+            log::warn!("Headless mode, not starting Gyroflow for {project_path:?}");
+            return;
+        }
         if cfg!(target_os = "macos") {
             let mut cmd = std::process::Command::new("osascript");
             if let Some(project) = project_path {
@@ -251,86 +301,21 @@ impl GyroflowPluginBase {
                             }
                         };
                         if let Err(e) = result {
-                            rfd::MessageDialog::new().set_description(format!("Unable to start Gyroflow: {e:?}")).show();
+                            notify(None, Notification::new(Severity::Error, format!("Unable to start Gyroflow: {e:?}")));
                         }
                     }
                 }
             } else {
-                rfd::MessageDialog::new().set_description("Unable to find Gyroflow app path. Make sure to run Gyroflow app at least once and that version is at least v1.6.0").show();
+                notify(None, Notification::new(Severity::Error, "Unable to find Gyroflow app path. Make sure to run Gyroflow app at least once and that version is at least v1.6.0"));
             }
         }
     }
 
-    pub fn get_param_definitions() -> [ParameterType; 13] {
-        [
-            ParameterType::HiddenString { id: "InstanceId" },
-            ParameterType::HiddenString { id: "ProjectPath" },
-            ParameterType::HiddenString { id: "ProjectData" },
-            ParameterType::HiddenString { id: "EmbeddedLensProfile" },
-            ParameterType::HiddenString { id: "EmbeddedPreset" },
-            ParameterType::Group { id: "ProjectGroup", label: "Gyroflow project", opened: true, parameters: vec![
-                ParameterType::Text    { id: "Status",            label: "Status",                   hint: "Status" },
-                ParameterType::Button  { id: "LoadCurrent",       label: "Load for current file",    hint: "Try to load project file for current video file, or try to stabilize that video file directly" },
-                ParameterType::Button  { id: "Browse",            label: "Browse",                   hint: "Browse for the Gyroflow project file" },
-                ParameterType::Button  { id: "LoadLens",          label: "Load preset/lens profile", hint: "Browse for the lens profile or a preset" },
-                ParameterType::Button  { id: "OpenGyroflow",      label: "Open Gyroflow",            hint: "Open project in Gyroflow" },
-                ParameterType::Button  { id: "ReloadProject",     label: "Reload project",           hint: "Reload currently loaded project" },
-                ParameterType::Button  { id: "OpenRecentProject", label: "Last saved project",       hint: "Load most recently saved project in the Gyroflow app" },
-            ] },
-            ParameterType::Group { id: "AdjustGroup", label: "Adjust parameters", opened: true, parameters: vec![
-                ParameterType::Slider   { id: "Smoothness",             label: "Smoothness",           hint: "Smoothness",                   min: 1.0,    max: 300.0, default: 50.0 },
-                ParameterType::Slider   { id: "ZoomLimit",              label: "Zoom limit",           hint: "Zoom limit",                   min: 51.0,   max: 300.0, default: 130.0 },
-                ParameterType::Slider   { id: "LensCorrectionStrength", label: "Lens correction",      hint: "Lens correction",              min: 0.0,    max: 100.0, default: 100.0 },
-                ParameterType::Slider   { id: "HorizonLockAmount",      label: "Horizon lock",         hint: "Horizon lock amount",          min: 0.0,    max: 100.0, default: 0.0 },
-                ParameterType::Slider   { id: "HorizonLockRoll",        label: "Horizon roll",         hint: "Horizon lock roll adjustment", min: -100.0, max: 100.0, default: 0.0 },
-                //ParameterType::Slider   { id: "PositionX",              label: "Position offset X",    hint: "Position offset X",            min: -100.0, max: 100.0, default: 0.0 },
-                //ParameterType::Slider   { id: "PositionY",              label: "Position offset Y",    hint: "Position offset Y",            min: -100.0, max: 100.0, default: 0.0 },
-                ParameterType::Slider   { id: "AdditionalPitch",        label: "Additional pitch",     hint: "Additional pitch rotation",    min: -180.0, max: 180.0, default: 0.0 },
-                ParameterType::Slider   { id: "AdditionalYaw",          label: "Additional yaw",       hint: "Additional yaw rotation",      min: -180.0, max: 180.0, default: 0.0 },
-                ParameterType::Slider   { id: "Rotation",               label: "Video rotation",       hint: "Video rotation",               min: -360.0, max: 360.0, default: 0.0 },
-                ParameterType::Slider   { id: "InputRotation",          label: "Input rotation",       hint: "Input rotation",               min: -360.0, max: 360.0, default: 0.0 },
-                ParameterType::Slider   { id: "Fov",                    label: "FOV",                  hint: "FOV",                          min: 0.1,    max: 3.0,   default: 1.0 },
-                ParameterType::Slider   { id: "VideoSpeed",             label: "Video speed",          hint: "Use this slider to change video speed or keyframe it, instead of built-in speed changes in the editor", min: 0.0001, max: 1000.0, default: 100.0 },
-                ParameterType::Checkbox { id: "DisableStretch",         label: "Disable Gyroflow's stretch", hint: "If you used Input stretch in the lens profile in Gyroflow, and you de-stretched the video separately in your editor (by setting anamorphic squeeze factor), check this to disable Gyroflow's internal stretching.", default: false },
-                ParameterType::Select   { id: "IntegrationMethod",      label: "Integration method",   hint: "IMU integration method", options: vec!["None", "Complementary", "VQF", "Simple gyro", "Simple gyro + accel", "Mahony", "Madgwick"], default: "VQF" },
-                //ParameterType::Slider   { id: "FusionStartFrame",       label: "Fusion Start Frame",   hint: "Fusion Start Frame (from Project Settings)", min: 0.0, max: 100000.0, default: 0.0 },
-            ] },
-            ParameterType::Group { id: "KeyframesGroup", label: "Keyframes", opened: false, parameters: vec![
-                ParameterType::Checkbox { id: "UseGyroflowsKeyframes", label: "Use Gyroflow's keyframes", hint: "Use internal Gyroflow's keyframes, instead of the editor ones.", default: false },
-                ParameterType::Checkbox { id: "StabilizationSpeedRamp",label: "Adjust stabilization to speed", hint: "When you speed ramp the clip, let Gyroflow adjust the stabilization amount to the video speed.", default: true },
-                ParameterType::Button   { id: "RecalculateKeyframes",  label: "Recalculate keyframes",         hint: "Recalculate keyframes after adjusting the splines (in Fusion mode)" },
-                ParameterType::Button   { id: "CreateCamera",  label: "Create camera", hint: "Create camera layer" },
-            ] },
-            ParameterType::Group { id: "OutputSizeGroup", label: "Output size", opened: false, parameters: vec![
-                ParameterType::Slider   { id: "OutputWidth",    label: "Width",  hint: "Width",  min: 1.0, max: 16384.0, default: 3840.0 },
-                ParameterType::Slider   { id: "OutputHeight",   label: "Height", hint: "Height", min: 1.0, max: 16384.0, default: 2160.0 },
-                ParameterType::Button   { id: "OutputSizeToTimeline", label: "Fit to timeline", hint: "Set the output size to the timeline dimensions" },
-                ParameterType::Button   { id: "OutputSizeSwap",  label: "Swap", hint: "Swap width and height" },
-                ParameterType::Select   { id: "Interpolation",   label: "Interpolation", hint: "Scaling interpolation method", options: vec!["Lanczos4", "RobidouxSharp", "Bilinear", "Bicubic", "Robidoux", "Mitchell", "CatmullRom"], default: "Lanczos4" },
-            ] },
-            ParameterType::Checkbox { id: "ToggleOverview",     label: "Stabilization overview",         hint: "Zooms out the view to see the stabilization results. Disable this before rendering.", default: false },
-            ParameterType::Checkbox { id: "DontDrawOutside",    label: "Don't draw outside source clip", hint: "When clip and timeline aspect ratio don't match, draw the final image inside the source clip, instead of drawing outside it.", default: false },
-            ParameterType::Checkbox { id: "IncludeProjectData", label: "Embed .gyroflow data in plugin", hint: "If you intend to share the project to someone else, the plugin can embed the Gyroflow project data including gyro data inside the video editor project. This way you don't have to share .gyroflow project files. Enabling this option will make the project bigger.", default: false },
-            ParameterType::Group { id: "InfoGroup", label: "Info", opened: true, parameters: vec![
-                ParameterType::Text { id: "LoadedProject",      label: "Loaded project",      hint: "Loaded project or video file" },
-                ParameterType::Text { id: "LoadedPreset",       label: "Loaded preset",       hint: "Loaded preset" },
-                ParameterType::Text { id: "LoadedLens",         label: "Loaded lens profile", hint: "Loaded lens profile" },
-            ] },
-        ]
+    pub fn get_param_definitions() -> &'static [ParamDefinition] {
+        PARAM_DEFINITIONS
     }
 }
 
-pub enum ParameterType {
-    HiddenString { id: &'static str },
-    TextBox      { id: &'static str, label: &'static str, hint: &'static str },
-    Text         { id: &'static str, label: &'static str, hint: &'static str },
-    Slider       { id: &'static str, label: &'static str, hint: &'static str, min: f64, max: f64, default: f64 },
-    Checkbox     { id: &'static str, label: &'static str, hint: &'static str, default: bool },
-    Button       { id: &'static str, label: &'static str, hint: &'static str },
-    Group        { id: &'static str, label: &'static str, opened: bool, parameters: Vec<ParameterType> },
-    Select       { id: &'static str, label: &'static str, hint: &'static str, options: Vec<&'static str>, default: &'static str },
-}
-
 #[derive(Debug, Clone)]
 pub enum TimeType {
     Frame(f64),
@@ -338,6 +323,18 @@ pub enum TimeType {
     Microseconds(i64),
     FrameOrMicrosecond((Option<f64>, Option<i64>))
 }
+impl TimeType {
+    pub fn timestamp_us(&self, fps: f64) -> i64 {
+        match *self {
+            TimeType::FrameOrMicrosecond((Some(f), None)) |
+            TimeType::Frame(f) => ((f / fps * 1_000_000.0)).round() as i64,
+            TimeType::Milliseconds(ms) => (ms * 1_000.0).round() as i64,
+            TimeType::Microseconds(us) => us,
+            TimeType::FrameOrMicrosecond((_,    Some(timestamp_us))) => timestamp_us,
+            TimeType::FrameOrMicrosecond((None, None)) => unreachable!(),
+        }
+    }
+}
 pub trait GyroflowPluginParams {
     fn set_enabled(&mut self, param: Params, enabled: bool) -> PluginResult<()>;
     fn set_label(&mut self, param: Params, label: &str) -> PluginResult<()>;
@@ -373,7 +370,34 @@ pub struct GyroflowPluginBaseInstance {
     pub keyframable_params: Arc<RwLock<KeyframableParams>>,
 
     #[serde(skip)]
-    pub managers: LruCache<String, Arc<StabilizationManager>>,
+    pub managers: ManagerCache,
+
+    /// Generation of the project files loaded in `managers`, see `watcher::generation`
+    #[serde(skip)]
+    pub project_generation: u64,
+
+    /// Load projects in a background thread and render pass-through frames until they are loaded.
+    /// Hosts turn it off with `set_interactive_render` for renders which end up in a file
+    #[serde(skip)]
+    pub background_loading: bool,
+    #[serde(skip)]
+    pub pending_load: Option<loader::PendingLoad>,
+
+    /// Path of the clip the effect is applied to, used to find projects which were moved together with the media
+    #[serde(skip)]
+    pub media_file_path: String,
+
+    /// Generation of the parameter values received from the Gyroflow app, see `ipc::params_update`
+    #[serde(skip)]
+    pub ipc_generation: u64,
+
+    /// Whether the user changed values which weren't saved to a project with `export_project` yet
+    #[serde(skip)]
+    pub unsaved_changes: bool,
+
+    /// Preset file loaded with `load_preset`, watched together with the project
+    #[serde(skip)]
+    pub preset_path: String,
 
     pub reload_values_from_project: bool,
 
@@ -395,6 +419,13 @@ impl Clone for GyroflowPluginBaseInstance {
     fn clone(&self) -> Self {
         Self {
             managers:                       self.managers.clone(),
+            project_generation:             self.project_generation,
+            background_loading:             self.background_loading,
+            pending_load:                   None,
+            media_file_path:                self.media_file_path.clone(),
+            ipc_generation:                 self.ipc_generation,
+            unsaved_changes:                self.unsaved_changes,
+            preset_path:                    self.preset_path.clone(),
             original_output_size:           self.original_output_size,
             original_video_size:            self.original_video_size,
             timeline_size:                  self.timeline_size,
@@ -415,7 +446,14 @@ impl Clone for GyroflowPluginBaseInstance {
 impl Default for GyroflowPluginBaseInstance {
     fn default() -> Self {
         Self {
-            managers:                       LruCache::new(std::num::NonZeroUsize::new(20).unwrap()),
+            managers:                       ManagerCache::for_instance("Instance"),
+            project_generation:             0,
+            background_loading:             !headless::is_headless(),
+            pending_load:                   None,
+            media_file_path:                String::new(),
+            ipc_generation:                 0,
+            unsaved_changes:                false,
+            preset_path:                    String::new(),
             original_output_size:           (0, 0),
             original_video_size:            (0, 0),
             timeline_size:                  (0, 0),
@@ -440,29 +478,86 @@ impl Default for GyroflowPluginBaseInstance {
 impl GyroflowPluginBaseInstance {
     pub fn update_loaded_state(&mut self, params: &mut dyn GyroflowPluginParams, loaded: bool) {
         let _ = params.set_enabled(Params::Fov, loaded);
-        let _ = params.set_enabled(Params::Smoothness, loaded);
+        smoothing::update_enabled(params, loaded);
         let _ = params.set_enabled(Params::ZoomLimit, loaded);
+        Self::update_zooming_enabled(params, loaded);
         let _ = params.set_enabled(Params::LensCorrectionStrength, loaded);
         let _ = params.set_enabled(Params::HorizonLockAmount, loaded);
         let _ = params.set_enabled(Params::HorizonLockRoll, loaded);
-        //let _ = params.set_enabled(Params::PositionX, loaded);
-        //let _ = params.set_enabled(Params::PositionY, loaded);
+        let _ = params.set_enabled(Params::PositionX, loaded);
+        let _ = params.set_enabled(Params::PositionY, loaded);
         let _ = params.set_enabled(Params::AdditionalPitch, loaded);
         let _ = params.set_enabled(Params::AdditionalYaw, loaded);
         let _ = params.set_enabled(Params::Rotation, loaded);
         let _ = params.set_enabled(Params::VideoSpeed, loaded);
         let _ = params.set_enabled(Params::DisableStretch, loaded);
         let _ = params.set_enabled(Params::IntegrationMethod, loaded);
+        let _ = params.set_enabled(Params::FrameReadoutTime, loaded);
+        let _ = params.set_enabled(Params::ReadoutDirection, loaded);
+        let _ = params.set_enabled(Params::SyncOffsetMs, loaded);
         let _ = params.set_enabled(Params::ToggleOverview, loaded);
         let _ = params.set_enabled(Params::ReloadProject, loaded);
         let _ = params.set_enabled(Params::OutputWidth, loaded);
         let _ = params.set_enabled(Params::OutputHeight, loaded);
         let _ = params.set_enabled(Params::OutputSizeToTimeline, loaded);
         let _ = params.set_enabled(Params::OutputSizeSwap, loaded);
-        let _ = params.set_string(Params::Status, if loaded { "OK" } else { "Project not loaded" });
+        let _ = params.set_string(Params::Status, if loaded { "OK" } else { PluginError::NoProject.status() });
         let _ = params.set_label(Params::OpenGyroflow, if loaded { "Open in Gyroflow" } else { "Open Gyroflow" });
     }
 
+    fn update_zooming_enabled(params: &mut dyn GyroflowPluginParams, loaded: bool) {
+        let dynamic = params.get_i32(Params::ZoomMode).unwrap_or(ZOOM_DYNAMIC) == ZOOM_DYNAMIC;
+        let _ = params.set_enabled(Params::ZoomMode, loaded);
+        let _ = params.set_enabled(Params::ZoomSpeed, loaded && dynamic);
+        let _ = params.set_enabled(Params::ZoomMethod, loaded && dynamic);
+    }
+    /// Sets the adaptive zoom window and method from `ZoomMode`, `ZoomSpeed` and `ZoomMethod`,
+    /// once they were read from the project or set by the user
+    fn set_zooming(params: &dyn GyroflowPluginParams, stab: &StabilizationManager) {
+        let mut gf_params = stab.params.write();
+        if overrides::is_set(params, Params::ZoomMode) || overrides::is_set(params, Params::ZoomSpeed) {
+            gf_params.adaptive_zoom_window = match params.get_i32(Params::ZoomMode) {
+                Ok(ZOOM_DISABLED) => -1.0,
+                Ok(ZOOM_STATIC)   => 0.0,
+                _ => params.get_f64(Params::ZoomSpeed).unwrap_or(4.0),
+            };
+        }
+        if overrides::is_set(params, Params::ZoomMethod) {
+            if let Ok(method) = params.get_i32(Params::ZoomMethod) {
+                gf_params.adaptive_zoom_method = method as _;
+            }
+        }
+    }
+
+    /// Sets the rolling shutter correction from `FrameReadoutTime` and `ReadoutDirection`,
+    /// once they were read from the project or set by the user
+    fn set_rolling_shutter(params: &dyn GyroflowPluginParams, stab: &StabilizationManager) {
+        let mut gf_params = stab.params.write();
+        if let Some(time) = params.get_f64(Params::FrameReadoutTime).ok().filter(|_| overrides::is_set(params, Params::FrameReadoutTime)) {
+            gf_params.frame_readout_time = time;
+        }
+        if let Some(direction) = params.get_i32(Params::ReadoutDirection).ok().filter(|_| overrides::is_set(params, Params::ReadoutDirection)) {
+            gf_params.frame_readout_direction = match direction {
+                1 => ReadoutDirection::BottomToTop,
+                2 => ReadoutDirection::LeftToRight,
+                3 => ReadoutDirection::RightToLeft,
+                _ => ReadoutDirection::TopToBottom,
+            };
+        }
+    }
+
+    /// `SyncOffsetMs` at its keyframes, or at 0 if it's not keyframed. Empty when there's nothing to adjust
+    fn sync_adjustment(&self, params: &dyn GyroflowPluginParams) -> sync_offset::Offsets {
+        if params.is_keyframed(Params::SyncOffsetMs) {
+            params.get_keyframes(Params::SyncOffsetMs).into_iter().map(|(t, v)| (t.timestamp_us(self.fps.max(1.0)), v)).collect()
+        } else {
+            match params.get_f64(Params::SyncOffsetMs) {
+                Ok(v) if v != 0.0 => [(0, v)].into(),
+                _ => Default::default()
+            }
+        }
+    }
+
     pub fn initialize_instance_id(&mut self, instance_id: &mut String) {
         if instance_id.is_empty() {
             self.ever_changed = true;
@@ -480,75 +575,105 @@ impl GyroflowPluginBaseInstance {
     }
     pub fn cache_keyframes(&mut self, params: &dyn GyroflowPluginParams, use_gyroflows_keyframes: bool, num_frames: usize, fps: f64) {
         let mut mgr = KeyframeManager::new();
-        macro_rules! cache_key {
-            ($typ:expr, $param:expr, $scale:expr) => {
-                if params.is_keyframed($param) {
-                    log::info!("param: {:?} is keyframed, cache_keyframes_every_frame: {}", $param, self.cache_keyframes_every_frame);
-                    if self.cache_keyframes_every_frame { // Query every frame
-                        for t in 0..num_frames {
-                            let time = t as f64;
-                            let timestamp_us = ((time / fps * 1_000_000.0)).round() as i64;
-
-                            if let Ok(v) = params.get_f64_at_time($param, TimeType::FrameOrMicrosecond((Some(time), Some(timestamp_us)))) {
-                                mgr.set(&$typ, timestamp_us, v / $scale);
-                            }
-                        }
-                    } else {
-                        // Cache only the keyframes at their timestamps
-                        for (t, v) in params.get_keyframes($param) {
-                            let timestamp_us = match t {
-                                TimeType::FrameOrMicrosecond((Some(f), None)) |
-                                TimeType::Frame(f) => ((f / fps * 1_000_000.0)).round() as i64,
-                                TimeType::Milliseconds(ms) => (ms * 1_000.0).round() as i64,
-                                TimeType::Microseconds(us) => us,
-                                TimeType::FrameOrMicrosecond((_,    Some(timestamp_us))) => timestamp_us,
-                                TimeType::FrameOrMicrosecond((None, None)) => unreachable!(),
-                            };
-
-                            mgr.set(&$typ, timestamp_us, v / $scale);
+        for def in ParamDefinition::all() {
+            let Some((typ, scale)) = &def.keyframe else { continue; };
+            // Parameters of other smoothing algorithms may drive the same keyframe
+            if !smoothing::is_active(params, def.param) { continue; }
+            if params.is_keyframed(def.param) {
+                log::info!("param: {:?} is keyframed, cache_keyframes_every_frame: {}", def.param, self.cache_keyframes_every_frame);
+                if self.cache_keyframes_every_frame { // Query every frame
+                    for t in 0..num_frames {
+                        let time = t as f64;
+                        let timestamp_us = ((time / fps * 1_000_000.0)).round() as i64;
+
+                        if let Ok(v) = params.get_f64_at_time(def.param, TimeType::FrameOrMicrosecond((Some(time), Some(timestamp_us)))) {
+                            mgr.set(typ, timestamp_us, v / scale);
                         }
                     }
                 } else {
-                    log::info!("param: {:?} NOT keyframed", $param);
-                    if let Ok(v) = params.get_f64($param) {
-                        mgr.set(&$typ, 0, v / $scale);
+                    // Cache only the keyframes at their timestamps
+                    for (t, v) in params.get_keyframes(def.param) {
+                        mgr.set(typ, t.timestamp_us(fps), v / scale);
                     }
                 }
-            };
+            } else {
+                log::info!("param: {:?} NOT keyframed", def.param);
+                if let Ok(v) = params.get_f64(def.param) {
+                    mgr.set(typ, 0, v / scale);
+                }
+            }
         }
-        cache_key!(KeyframeType::Fov,                       Params::Fov,                    1.0);
-        cache_key!(KeyframeType::MaxZoom,                   Params::ZoomLimit,              1.0);
-        cache_key!(KeyframeType::SmoothingParamSmoothness,  Params::Smoothness,             100.0);
-        cache_key!(KeyframeType::LensCorrectionStrength,    Params::LensCorrectionStrength, 100.0);
-        cache_key!(KeyframeType::LockHorizonAmount,         Params::HorizonLockAmount,      1.0);
-        cache_key!(KeyframeType::LockHorizonRoll,           Params::HorizonLockRoll,        1.0);
-        cache_key!(KeyframeType::VideoSpeed,                Params::VideoSpeed,             100.0);
-        cache_key!(KeyframeType::VideoRotation,             Params::Rotation,               1.0);
-        //cache_key!(KeyframeType::ZoomingCenterX,            Params::PositionX,              100.0);
-        //cache_key!(KeyframeType::ZoomingCenterY,            Params::PositionY,              100.0);
-        cache_key!(KeyframeType::AdditionalRotationX,       Params::AdditionalYaw,          1.0);
-        cache_key!(KeyframeType::AdditionalRotationY,       Params::AdditionalPitch,        1.0);
 
         let mut kparams = self.keyframable_params.write();
         kparams.use_gyroflows_keyframes = use_gyroflows_keyframes;
         kparams.cached_keyframes = mgr;
     }
 
-    pub fn stab_manager(&mut self, params: &mut dyn GyroflowPluginParams, manager_cache: &Mutex<LruCache<String, Arc<StabilizationManager>>>, out_size: (usize, usize), open_gyroflow_if_no_data: bool) -> PluginResult<Arc<StabilizationManager>> {
+    fn new_manager(&self, params: &dyn GyroflowPluginParams) -> StabilizationManager {
+        let mut stab = StabilizationManager::default();
+        lens_db::share_with(&mut stab);
+        {
+            let mut stab = stab.stabilization.write();
+            stab.share_wgpu_instances = true;
+            stab.interpolation = match params.get_i32(Params::Interpolation) {
+                Ok(1) => gyroflow_core::stabilization::Interpolation::RobidouxSharp,
+                Ok(2) => gyroflow_core::stabilization::Interpolation::Bilinear,
+                Ok(3) => gyroflow_core::stabilization::Interpolation::Bicubic,
+                Ok(4) => gyroflow_core::stabilization::Interpolation::Robidoux,
+                Ok(5) => gyroflow_core::stabilization::Interpolation::Mitchell,
+                Ok(6) => gyroflow_core::stabilization::Interpolation::CatmullRom,
+                _     => gyroflow_core::stabilization::Interpolation::Lanczos4,
+            };
+            log::info!("Interpolation: {:?}", stab.interpolation);
+        }
+        stab
+    }
+
+    pub fn stab_manager(&mut self, params: &mut dyn GyroflowPluginParams, manager_cache: &Mutex<ManagerCache>, out_size: (usize, usize), open_gyroflow_if_no_data: bool) -> PluginResult<Arc<StabilizationManager>> {
         let mut disable_stretch = params.get_bool(Params::DisableStretch)?;
 
-        let instance_id = params.get_string(Params::InstanceId)?;
         let path = params.get_string(Params::ProjectPath)?;
         if path.is_empty() {
             self.update_loaded_state(params, false);
-            return Err("Path is empty".into());
+            return Err(PluginError::NoProject.into());
         }
 
+        // Values changed in the Gyroflow app, applied as if the user edited them
+        if let Some((generation, values)) = ipc::params_update(&path, self.ipc_generation) {
+            self.ipc_generation = generation;
+            for (param, value) in values {
+                // A value the host can't take mustn't fail every render
+                if let Err(e) = params.set_f64(param, value).and_then(|_| self.param_changed(params, manager_cache, param, true)) {
+                    log::warn!("Failed to apply {param:?} = {value} from the Gyroflow app: {e:?}");
+                }
+            }
+        }
+        let instance_id = params.get_string(Params::InstanceId)?;
+
         if self.timeline_size == (0, 0) {
             self.timeline_size = out_size;
         }
 
-        let key = format!("{path}{disable_stretch}{instance_id}");
+        let watch_files = params.get_bool(Params::WatchProjectFiles).unwrap_or_default();
+        if watch_files {
+            let generation = watcher::generation(&path);
+            if generation != self.project_generation {
+                log::info!("Project files changed, reloading: {path}");
+                self.project_generation = generation;
+                if !self.preset_path.is_empty() {
+                    let preset_path = self.preset_path.clone();
+                    if let Err(e) = Self::apply_preset(params, std::path::Path::new(&preset_path)) {
+                        log::warn!("Failed to reload the preset {preset_path}: {e:?}");
+                    }
+                }
+                if path.ends_with(".gyroflow") && params.get_bool(Params::IncludeProjectData)? {
+                    self.param_changed(params, manager_cache, Params::IncludeProjectData, false)?;
+                }
+                self.param_changed(params, manager_cache, Params::ReloadProject, false)?;
+            }
+        }
+
+        let key = format!("{path}{disable_stretch}{instance_id}{}", self.project_generation);
         let cloned = manager_cache.lock().get(&key).map(Arc::clone);
         let stab = if let Some(stab) = cloned {
             // Cache it in this instance as well
@@ -558,136 +683,81 @@ impl GyroflowPluginBaseInstance {
             self.set_keyframe_provider(&stab);
             stab
         } else {
-            log::info!("new stab manager for key: {key}");
-            let mut stab = StabilizationManager::default();
-            {
-                // Find first lens profile database with loaded profiles
-                let lock = manager_cache.lock();
-                for (_, v) in lock.iter() {
-                    if v.lens_profile_db.read().loaded {
-                        stab.lens_profile_db = v.lens_profile_db.clone();
-                        break;
+            let background_loading = self.background_loading;
+            let result = match self.pending_load.as_mut().filter(|x| x.key == key).map(|x| (if background_loading { x.poll() } else { x.wait() }, x.progress())) {
+                Some((None, progress)) => {
+                    self.set_status(params, &format!("Loading... {:.0}%", progress * 100.0), &PluginError::Loading.hint(), false);
+                    return Err(PluginError::Loading.into());
+                },
+                Some((Some(result), _)) => {
+                    if result.is_ok() {
+                        self.pending_load = None;
                     }
-                }
-            }
-            {
-                let mut stab = stab.stabilization.write();
-                stab.share_wgpu_instances = true;
-                stab.interpolation = match params.get_i32(Params::Interpolation) {
-                    Ok(1) => gyroflow_core::stabilization::Interpolation::RobidouxSharp,
-                    Ok(2) => gyroflow_core::stabilization::Interpolation::Bilinear,
-                    Ok(3) => gyroflow_core::stabilization::Interpolation::Bicubic,
-                    Ok(4) => gyroflow_core::stabilization::Interpolation::Robidoux,
-                    Ok(5) => gyroflow_core::stabilization::Interpolation::Mitchell,
-                    Ok(6) => gyroflow_core::stabilization::Interpolation::CatmullRom,
-                    _     => gyroflow_core::stabilization::Interpolation::Lanczos4,
-                };
-                log::info!("Interpolation: {:?}", stab.interpolation);
-            }
-
-            if !path.ends_with(".gyroflow") {
-                let url = filesystem::path_to_url(&path);
-                let mut file = filesystem::open_file(&url, false, false)?;
-                let filesize = file.size;
-                match stab.load_video_file(file.get_file(), filesize, &url, None, true) {
-                    Ok(md) => {
-                        if out_size != (0, 0) {
-                            stab.params.write().output_size = out_size; // Default to timeline output size
-                        }
-                        if let Some(preset_out_size) = stab.input_file.read().preset_output_size {
-                            stab.params.write().output_size = preset_out_size;
-                        }
-
-                        if let Ok(d) = params.get_string(Params::EmbeddedLensProfile) {
-                            if !d.is_empty() {
-                                if let Err(e) = stab.load_lens_profile(&d) {
-                                    rfd::MessageDialog::new()
-                                        .set_description(&format!("Failed to load lens profile: {e:?}"))
-                                        .show();
-                                }
-                            }
-                        }
-                        if let Ok(d) = params.get_string(Params::EmbeddedPreset) {
-                            if !d.is_empty() {
-                                let mut is_preset = false;
-                                if let Err(e) = stab.import_gyroflow_data(d.as_bytes(), true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
-                                    rfd::MessageDialog::new()
-                                        .set_description(&format!("Failed to load preset: {e:?}"))
-                                        .show();
-                                }
-                            }
-                        }
-                        if params.get_bool(Params::IncludeProjectData)? {
-                            if let Ok(data) = stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
-                                let data = GyroflowPluginBase::maybe_inject_disable_stretch(&data, disable_stretch);
-                                params.set_string(Params::ProjectData, &data)?;
-                            }
-                        }
-                        if md.rotation != 0 && self.reload_values_from_project {
-                            let r = ((360 - md.rotation) % 360) as f64;
-                            params.set_f64(Params::InputRotation, r)?;
-                            stab.params.write().video_rotation = r;
-                        }
-                        params.set_string(Params::LoadedProject, &filesystem::get_filename(&filesystem::path_to_url(&path)))?;
-                        if !stab.gyro.read().file_metadata.read().has_accurate_timestamps && open_gyroflow_if_no_data {
-                            GyroflowPluginBase::open_gyroflow(params.get_string(Params::ProjectPath).ok().as_deref());
-                        }
-                    },
-                    Err(e) => {
-                        let embedded_data = params.get_string(Params::ProjectData)?;
-                        if !embedded_data.is_empty() {
-                            let mut is_preset = false;
-                            stab.import_gyroflow_data(embedded_data.as_bytes(), true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true).map_err(|e| {
-                                self.update_loaded_state(params, false);
-                                format!("load_gyro_data error: {e}")
-                            })?;
-                        } else {
-                            log::error!("An error occured: {e:?}");
-                            self.update_loaded_state(params, false);
-                            params.set_string(Params::Status, "Failed to load file info!")?;
-                            params.set_hint(Params::Status, &format!("Error loading {path}: {e:?}."))?;
-                            if open_gyroflow_if_no_data {
-                                GyroflowPluginBase::open_gyroflow(params.get_string(Params::ProjectPath).ok().as_deref());
-                            }
-                            return Err(e.into());
-                        }
+                    result
+                },
+                None => {
+                    log::info!("new stab manager for key: {key}");
+                    let request = loader::LoadRequest {
+                        path:                 path_remap::resolve(&path, &self.media_file_path),
+                        out_size,
+                        embedded_lens:        embedded::get(params, Params::EmbeddedLensProfile).unwrap_or_default(),
+                        embedded_preset:      embedded::get(params, Params::EmbeddedPreset).unwrap_or_default(),
+                        project_data:         embedded::get(params, Params::ProjectData)?,
+                        include_project_data: params.get_bool(Params::IncludeProjectData)?,
+                        disable_stretch,
+                        read_video_rotation:  self.always_set_input_rotation,
+                    };
+                    let stab = self.new_manager(params);
+                    if self.background_loading {
+                        self.pending_load = Some(loader::PendingLoad::start(key.clone(), request, stab));
+                        self.set_status(params, "Loading... 0%", &PluginError::Loading.hint(), false);
+                        return Err(PluginError::Loading.into());
                     }
+                    loader::load(&request, stab, Default::default(), Default::default())
                 }
-            } else {
-                let project_data = {
-                    if params.get_bool(Params::IncludeProjectData)? && !params.get_string(Params::ProjectData)?.is_empty() {
-                        params.get_string(Params::ProjectData)?
-                    } else if let Ok(data) = std::fs::read_to_string(&path) {
-                        if params.get_bool(Params::IncludeProjectData)? {
-                            params.set_string(Params::ProjectData, &data)?;
-                        } else {
-                            params.set_string(Params::ProjectData, "")?;
-                        }
-                        data
-                    } else {
-                        "".to_string()
-                    }
-                };
-                let mut is_preset = false;
-                stab.import_gyroflow_data(project_data.as_bytes(), true, Some(&filesystem::path_to_url(&path)), |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true).map_err(|e| {
+            };
+            let loaded = match result {
+                Ok(loaded) => loaded,
+                Err(e) => {
                     self.update_loaded_state(params, false);
-                    format!("load_gyro_data error: {e}")
-                })?;
-                params.set_string(Params::LoadedProject, &filesystem::get_filename(&filesystem::path_to_url(&path)))?;
-
-                if self.always_set_input_rotation {
-                    let url = stab.input_file.read().url.clone();
-                    let mut file = filesystem::open_file(&url, false, false)?;
-                    let filesize = file.size;
-                    if let Ok(video_md) = gyroflow_core::util::get_video_metadata(file.get_file(), filesize, &url) {
-                        if video_md.rotation != 0 && self.reload_values_from_project {
-                            let r = ((360 - video_md.rotation) % 360) as f64;
-                            params.set_f64(Params::InputRotation, r)?;
-                            stab.params.write().video_rotation = r;
-                        }
+                    self.set_error_status(params, &e);
+                    if matches!(e, PluginError::VideoUnreadable { .. }) && open_gyroflow_if_no_data {
+                        GyroflowPluginBase::open_gyroflow(Some(&path));
                     }
+                    return Err(e.into());
                 }
+            };
+
+            let mut stab = loaded.stab;
+            for e in &loaded.warnings {
+                notify(Some(&mut *params), e.into());
+            }
+            if let Some(data) = &loaded.project_data {
+                embedded::set(params, Params::ProjectData, data)?;
             }
+            if let Some(rotation) = loaded.video_rotation {
+                if rotation != 0 && self.reload_values_from_project {
+                    let r = ((360 - rotation) % 360) as f64;
+                    params.set_f64(Params::InputRotation, r)?;
+                    stab.params.write().video_rotation = r;
+                }
+            }
+            if loaded.set_loaded_project {
+                // Show the full path when the project was found somewhere else than where it was saved
+                let loaded_project = if loaded.path != path { loaded.path.clone() } else { filesystem::get_filename(&filesystem::path_to_url(&path)) };
+                params.set_string(Params::LoadedProject, &loaded_project)?;
+                let alternatives = if self.media_file_path.is_empty() { Vec::new() } else { GyroflowPluginBase::get_project_paths(&self.media_file_path) };
+                let alternatives = alternatives.into_iter().filter(|x| *x != loaded.path).collect::<Vec<_>>();
+                if alternatives.is_empty() {
+                    let _ = params.set_hint(Params::LoadedProject, &loaded.path);
+                } else {
+                    let _ = params.set_hint(Params::LoadedProject, &format!("{}\n\nOther projects found for this clip:\n{}", loaded.path, alternatives.join("\n")));
+                }
+            }
+            if loaded.is_video_file && loaded.set_loaded_project && !stab.gyro.read().file_metadata.read().has_accurate_timestamps && open_gyroflow_if_no_data {
+                GyroflowPluginBase::open_gyroflow(Some(&loaded.path));
+            }
+            let resolved_path = loaded.path;
 
             let loaded = {
                 stab.params.write().calculate_ramped_timestamps(&stab.keyframes.read(), false, true);
@@ -699,17 +769,29 @@ impl GyroflowPluginBaseInstance {
                 let loaded = gf_params.duration_ms > 0.0;
                 if loaded && self.reload_values_from_project {
                     self.reload_values_from_project = false;
+                    overrides::set(params, overrides::PARAMS)?;
+                    smoothing::read(params, &stab)?;
                     let smooth = stab.smoothing.read();
-                    let smoothness = smooth.current().get_parameter("smoothness");
                     params.set_f64(Params::Fov,                    gf_params.fov)?;
-                    params.set_f64(Params::Smoothness,             smoothness * 100.0)?;
                     params.set_f64(Params::ZoomLimit,              gf_params.max_zoom.unwrap_or(0.0))?;
+                    params.set_i32(Params::ZoomMode,               zoom_mode(gf_params.adaptive_zoom_window))?;
+                    if gf_params.adaptive_zoom_window > 0.0 {
+                        params.set_f64(Params::ZoomSpeed,          gf_params.adaptive_zoom_window)?;
+                    }
+                    params.set_i32(Params::ZoomMethod,             gf_params.adaptive_zoom_method as i32)?;
+                    params.set_f64(Params::FrameReadoutTime,       gf_params.frame_readout_time)?;
+                    params.set_i32(Params::ReadoutDirection, match gf_params.frame_readout_direction {
+                        ReadoutDirection::TopToBottom => 0,
+                        ReadoutDirection::BottomToTop => 1,
+                        ReadoutDirection::LeftToRight => 2,
+                        ReadoutDirection::RightToLeft => 3,
+                    })?;
                     params.set_f64(Params::LensCorrectionStrength, (gf_params.lens_correction_amount * 100.0).min(100.0))?;
                     params.set_f64(Params::HorizonLockAmount,      if smooth.horizon_lock.lock_enabled { smooth.horizon_lock.horizonlockpercent } else { 0.0 })?;
                     params.set_f64(Params::HorizonLockRoll,        if smooth.horizon_lock.lock_enabled { smooth.horizon_lock.horizonroll } else { 0.0 })?;
                     params.set_f64(Params::VideoSpeed,             gf_params.video_speed * 100.0)?;
-                    //params.set_f64(Params::PositionX,              gf_params.adaptive_zoom_center_offset.0 * 100.0)?;
-                    //params.set_f64(Params::PositionY,              gf_params.adaptive_zoom_center_offset.1 * 100.0)?;
+                    params.set_f64(Params::PositionX,              gf_params.adaptive_zoom_center_offset.0 * 100.0)?;
+                    params.set_f64(Params::PositionY,              gf_params.adaptive_zoom_center_offset.1 * 100.0)?;
                     params.set_f64(Params::AdditionalYaw,          gf_params.additional_rotation.0)?;
                     params.set_f64(Params::AdditionalPitch,        gf_params.additional_rotation.1)?;
                     params.set_f64(Params::Rotation,               gf_params.video_rotation)?;
@@ -739,30 +821,13 @@ impl GyroflowPluginBaseInstance {
                     for k in all_keys {
                         if let Some(keys) = keyframes.get_keyframes(k) {
                             if !keys.is_empty() {
-                                macro_rules! set_keys {
-                                    ($name:expr, $scale:expr) => {
-                                        params.clear_keyframes($name)?;
-                                        for (ts, v) in keys {
-                                            let ts = if k == &KeyframeType::VideoSpeed { gf_params.get_source_timestamp_at_ramped_timestamp(*ts) } else { *ts };
-                                            let time = (((ts as f64 / 1000.0) * gf_params.fps) / 1000.0).round();
-                                            params.set_f64_at_time($name, TimeType::Frame(time), v.value * $scale)?;
-                                        }
-                                    };
-                                }
-                                match k {
-                                    KeyframeType::Fov                      => { set_keys!(Params::Fov,                    1.0); },
-                                    KeyframeType::SmoothingParamSmoothness => { set_keys!(Params::Smoothness,             100.0); },
-                                    KeyframeType::MaxZoom                  => { set_keys!(Params::ZoomLimit,              1.0); },
-                                    KeyframeType::LensCorrectionStrength   => { set_keys!(Params::LensCorrectionStrength, 100.0); },
-                                    KeyframeType::LockHorizonAmount        => { set_keys!(Params::HorizonLockAmount,      1.0); },
-                                    KeyframeType::LockHorizonRoll          => { set_keys!(Params::HorizonLockRoll,        1.0); },
-                                    KeyframeType::VideoSpeed               => { set_keys!(Params::VideoSpeed,             100.0); },
-                                    KeyframeType::VideoRotation            => { set_keys!(Params::Rotation,               1.0); },
-                                    //KeyframeType::ZoomingCenterX           => { set_keys!(Params::PositionX,              100.0); },
-                                    //KeyframeType::ZoomingCenterY           => { set_keys!(Params::PositionY,              100.0); },
-                                    KeyframeType::AdditionalRotationX      => { set_keys!(Params::AdditionalYaw,          1.0); },
-                                    KeyframeType::AdditionalRotationY      => { set_keys!(Params::AdditionalPitch,        1.0); },
-                                    _ => { }
+                                if let Some((param, scale)) = smoothing::param_for_keyframe(params, k).and_then(|def| Some((def.param, def.keyframe.as_ref()?.1))) {
+                                    params.clear_keyframes(param)?;
+                                    for (ts, v) in keys {
+                                        let ts = if k == &KeyframeType::VideoSpeed { gf_params.get_source_timestamp_at_ramped_timestamp(*ts) } else { *ts };
+                                        let time = (((ts as f64 / 1000.0) * gf_params.fps) / 1000.0).round();
+                                        params.set_f64_at_time(param, TimeType::Frame(time), v.value * scale)?;
+                                    }
                                 }
                             }
                         }
@@ -784,7 +849,7 @@ impl GyroflowPluginBaseInstance {
                         .unwrap_or(false)
                 };
                 for param_id in [Params::EmbeddedLensProfile, Params::EmbeddedPreset, Params::ProjectData] {
-                    if let Ok(d) = params.get_string(param_id) {
+                    if let Ok(d) = embedded::get(params, param_id) {
                         if !d.is_empty() && has_flag(&d) {
                             disable_stretch = true;
                             let _ = params.set_bool(Params::DisableStretch, true);
@@ -835,13 +900,24 @@ impl GyroflowPluginBaseInstance {
                 gyro.integration_method = im as usize;
                 gyro.apply_transforms();
             }
+            smoothing::apply(params, &stab);
+            Self::set_zooming(params, &stab);
+            Self::set_rolling_shutter(params, &stab);
+            let sync_adjustment = self.sync_adjustment(params);
+            let project_offsets = sync_offset::apply_new(&stab, &sync_adjustment);
 
             stab.invalidate_smoothing();
             stab.recompute_blocking();
             let inverse = !(params.get_bool(Params::UseGyroflowsKeyframes)? && stab.keyframes.read().is_keyframed_internally(&KeyframeType::VideoSpeed));
             stab.params.write().calculate_ramped_timestamps(&stab.keyframes.read(), inverse, inverse);
 
+            if watch_files && loaded {
+                watcher::watch(&path, &[&resolved_path, &stab.lens.read().path_to_file, &self.preset_path]);
+            }
+
             let stab = Arc::new(stab);
+            sync_offset::register(&stab, project_offsets, sync_adjustment);
+            ipc::register(&path, &stab);
             // Insert to static global cache
             manager_cache.lock().put(key.to_owned(), stab.clone());
             // Cache it in this instance as well
@@ -853,14 +929,17 @@ impl GyroflowPluginBaseInstance {
         Ok(stab)
     }
 
-    pub fn clear_stab(&mut self, manager_cache: &Mutex<LruCache<String, Arc<StabilizationManager>>>) {
+    pub fn clear_stab(&mut self, manager_cache: &Mutex<ManagerCache>) {
+        // Cancels the background loading
+        self.pending_load = None;
+
         let local_keys = self.managers.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
         self.managers.clear();
 
         // If there are no more local references, delete it from global cache
         let mut lock = manager_cache.lock();
         for key in local_keys {
-            if let Some(v) = lock.get(&key) {
+            if let Some(v) = lock.peek(&key) {
                 if Arc::strong_count(v) == 1 {
                     lock.pop(&key);
                 }
@@ -868,6 +947,24 @@ impl GyroflowPluginBaseInstance {
         }
     }
 
+    /// Whether a project is being loaded in the background, hosts should render pass-through frames
+    pub fn is_loading(&self) -> bool {
+        self.pending_load.as_ref().is_some_and(|x| !x.failed())
+    }
+    /// Whether the host should render the source frame unchanged after `stab_manager` returned `error`, instead of failing the render
+    pub fn pass_through_on_error(&self, error: &dyn std::error::Error) -> bool {
+        match headless::failure_mode() {
+            Some(headless::FailureMode::PassThrough) => { log::error!("Render failed, passing the frame through: {error}"); true },
+            Some(headless::FailureMode::Fail)        => { log::error!("Render failed: {error}"); false },
+            None => self.is_loading()
+        }
+    }
+    /// Called by the hosts before `stab_manager`. Only frames shown in the editor's viewer are passed through while the project
+    /// is loaded in the background, renders to a file (exports, render queues) wait for the project to be loaded
+    pub fn set_interactive_render(&mut self, interactive: bool) {
+        self.background_loading = interactive && !headless::is_headless();
+    }
+
     pub fn disable_opencl(&mut self) {
         if !self.opencl_disabled {
             unsafe { std::env::set_var("NO_OPENCL", "1") };
@@ -884,7 +981,36 @@ impl GyroflowPluginBaseInstance {
             }
         }
     }
+    /// Runs the clip diagnostics and displays the most severe problem in the `Status` parameter
+    pub fn update_status(&mut self, params: &mut dyn GyroflowPluginParams, stab: &StabilizationManager, clip: &ClipInfo) -> Vec<PluginError> {
+        let problems = diagnostics::check(stab, clip);
+        match problems.first() {
+            Some(e) => self.set_error_status(params, e),
+            None    => self.set_status(params, "OK", "OK", true),
+        }
+        problems
+    }
+    pub fn set_error_status(&mut self, params: &mut dyn GyroflowPluginParams, error: &PluginError) {
+        if params.get_string(Params::Status).unwrap_or_default() != error.status() {
+            error.log();
+        }
+        self.set_status(params, error.status(), &error.hint(), false);
+    }
 
+    fn pick_file(d: rfd::FileDialog) -> Option<std::path::PathBuf> {
+        if headless::is_headless() {
+            log::warn!("Headless mode, file dialogs are disabled");
+            return None;
+        }
+        d.pick_file()
+    }
+    fn pick_save_file(d: rfd::FileDialog) -> Option<std::path::PathBuf> {
+        if headless::is_headless() {
+            log::warn!("Headless mode, file dialogs are disabled");
+            return None;
+        }
+        d.save_file()
+    }
     pub fn browse(current_path: &str) -> String {
         let mut d = rfd::FileDialog::new()
             .add_filter("Project and video files", &["mp4", "mov", "mxf", "braw", "r3d", "insv", "gyroflow"]);
@@ -893,14 +1019,88 @@ impl GyroflowPluginBaseInstance {
                 d = d.set_directory(path);
             }
         }
-        if let Some(d) = d.pick_file() {
+        if let Some(d) = Self::pick_file(d) {
             d.display().to_string()
         } else {
             String::new()
         }
     }
 
-    pub fn param_changed(&mut self, params: &mut dyn GyroflowPluginParams, manager_cache: &Mutex<LruCache<String, Arc<StabilizationManager>>>, param: Params, user_edited: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// Writes the project data embedded in the plugin to `target` and loads the project from that file
+    pub fn extract_project_data(&mut self, params: &mut dyn GyroflowPluginParams, target: &std::path::Path) -> PluginResult<()> {
+        let data = embedded::get(params, Params::ProjectData)?;
+        if data.is_empty() {
+            return Err(PluginError::NoProject.into());
+        }
+        // The flag is only meaningful for the plugin, keep it in the parameter instead
+        let (data, disable_stretch) = GyroflowPluginBase::take_disable_stretch(&data);
+        if disable_stretch {
+            params.set_bool(Params::DisableStretch, true)?;
+        }
+        std::fs::write(target, data)?;
+        let target = target.to_string_lossy().to_string();
+        log::info!("Embedded project data saved to {target}");
+        params.set_string(Params::ProjectPath, &target)?;
+        Ok(())
+    }
+
+    /// Saves the loaded project with the plugin values and the editor keyframes merged in.
+    /// `.gyroflow` projects are updated, for video files a new project is saved next to the video. Returns the path of the project
+    pub fn export_project(&mut self, params: &mut dyn GyroflowPluginParams) -> PluginResult<String> {
+        let path = params.get_string(Params::ProjectPath)?;
+        let Some((_, stab)) = self.managers.peek_lru() else { return Err(PluginError::NoProject.into()); };
+        let stab = stab.clone();
+
+        let source = if path.ends_with(".gyroflow") { path_remap::resolve(&path, &self.media_file_path) } else { sequence::media_path(&path) };
+        let target = project_export::target_path(&source);
+        // Keeps what the app stored in the project, like the render settings
+        let existing = [&target, &source].into_iter()
+            .filter(|x| x.ends_with(".gyroflow"))
+            .find_map(|x| serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(x).ok()?).ok());
+        let data = {
+            let kparams = self.keyframable_params.read();
+            project_export::export(&stab, &kparams.cached_keyframes, kparams.use_gyroflows_keyframes, existing.as_ref())?
+        };
+        std::fs::write(&target, data)?;
+        log::info!("Project with the plugin values saved to {target}");
+        self.unsaved_changes = false;
+
+        // The sync adjustment is now in the project's offsets
+        sync_offset::bake(&stab);
+        if params.is_keyframed(Params::SyncOffsetMs) {
+            params.clear_keyframes(Params::SyncOffsetMs)?;
+        }
+        params.set_f64(Params::SyncOffsetMs, 0.0)?;
+
+        if target != path {
+            params.set_string(Params::ProjectPath, &target)?;
+        }
+        Ok(target)
+    }
+
+    /// Sets the parameters from the preset file and embeds the preset, so it's also applied by Gyroflow
+    pub fn load_preset(&mut self, params: &mut dyn GyroflowPluginParams, manager_cache: &Mutex<ManagerCache>, path: &std::path::Path) -> PluginResult<()> {
+        Self::apply_preset(params, path)?;
+        self.preset_path = path.to_string_lossy().to_string();
+        self.unsaved_changes = true;
+        if !self.ever_changed {
+            self.ever_changed = true;
+            params.set_string(Params::InstanceId, &format!("{}", fastrand::u64(..)))?;
+        }
+        self.clear_stab(manager_cache);
+        Ok(())
+    }
+    fn apply_preset(params: &mut dyn GyroflowPluginParams, path: &std::path::Path) -> PluginResult<()> {
+        let (name, data) = presets::load(path)?;
+        let changed = presets::apply(params, &serde_json::from_str(&data)?)?;
+        log::info!("Loaded preset {name} from {path:?}: {changed:?}");
+        overrides::set(params, &changed)?;
+        embedded::set(params, Params::EmbeddedPreset, &data)?;
+        params.set_string(Params::LoadedPreset, &name)?;
+        Ok(())
+    }
+
+    pub fn param_changed(&mut self, params: &mut dyn GyroflowPluginParams, manager_cache: &Mutex<ManagerCache>, param: Params, user_edited: bool) -> Result<(), Box<dyn std::error::Error>> {
         if param == Params::Browse {
             let new_path = Self::browse(&params.get_string(Params::ProjectPath)?);
             if !new_path.is_empty() {
@@ -914,14 +1114,14 @@ impl GyroflowPluginBaseInstance {
 
             let mut d = rfd::FileDialog::new().add_filter("Lens profiles and presets", &["json", "gyroflow"]);
             d = d.set_directory(lens_directory);
-            if let Some(d) = d.pick_file() {
+            if let Some(d) = Self::pick_file(d) {
                 let d = d.display().to_string();
                 if !d.is_empty() {
                     if let Ok(contents) = std::fs::read_to_string(&d) {
                         if d.ends_with(".json") {
-                            params.set_string(Params::EmbeddedLensProfile, &contents)?;
+                            embedded::set(params, Params::EmbeddedLensProfile, &contents)?;
                         } else {
-                            params.set_string(Params::EmbeddedPreset, &contents)?;
+                            embedded::set(params, Params::EmbeddedPreset, &contents)?;
                         }
                         self.reload_values_from_project = true;
                     }
@@ -929,8 +1129,57 @@ impl GyroflowPluginBaseInstance {
                 }
             }
         }
+        if param == Params::LoadPreset {
+            let index = params.get_i32(Params::LoadPreset)?;
+            if index > 0 {
+                // The list is in the order of the options, without the first one
+                if let Some((_, path)) = presets::listed().get(index as usize - 1) {
+                    self.load_preset(params, manager_cache, path)?;
+                }
+                params.set_i32(Params::LoadPreset, 0)?;
+            }
+        }
+        if param == Params::SavePreset {
+            let d = rfd::FileDialog::new()
+                .add_filter("Gyroflow preset", &[presets::EXTENSION])
+                .set_directory(presets::presets_dir())
+                .set_file_name(format!("preset.{}", presets::EXTENSION));
+            if let Some(target) = Self::pick_save_file(d) {
+                let name = target.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+                presets::save(&presets::from_params(params, &name)?, &target)?;
+                params.set_string(Params::LoadedPreset, &name)?;
+            }
+        }
         if param == Params::OpenGyroflow {
-            GyroflowPluginBase::open_gyroflow(params.get_string(Params::ProjectPath).ok().as_deref());
+            let mut path = params.get_string(Params::ProjectPath).ok();
+            // Show the values rendered by the editor in the app
+            if self.unsaved_changes && !self.managers.is_empty() {
+                match self.export_project(params) {
+                    Ok(target) => path = Some(target),
+                    Err(e) => log::error!("Failed to save the plugin values to the project: {e:?}")
+                }
+            }
+            GyroflowPluginBase::open_gyroflow(path.as_deref());
+        }
+        if param == Params::ExtractProjectData {
+            if embedded::get(params, Params::ProjectData)?.is_empty() {
+                notify(Some(&mut *params), Notification::new(Severity::Warning, "There's no Gyroflow project data embedded in the plugin. Enable \"Embed .gyroflow data in plugin\" to store it.").with_title("No embedded project data"));
+            } else {
+                let path = params.get_string(Params::ProjectPath)?;
+                let file_name = std::path::Path::new(&path).with_extension("gyroflow").file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_else(|| "project.gyroflow".into());
+                let mut d = rfd::FileDialog::new().add_filter("Gyroflow project", &["gyroflow"]).set_file_name(file_name);
+                let media_dir = std::path::Path::new(&self.media_file_path).parent().map(|x| x.to_path_buf());
+                if let Some(dir) = std::path::Path::new(&path).parent().map(|x| x.to_path_buf()).into_iter().chain(media_dir).find(|x| x.is_dir()) {
+                    d = d.set_directory(dir);
+                }
+                if let Some(target) = Self::pick_save_file(d) {
+                    self.extract_project_data(params, &target)?;
+                    let saved = Notification::new(Severity::Info, format!("Project saved to {}.", target.display())).with_title("Project saved");
+                    if notification::ask(Some(&mut *params), saved, "Do you want to open it in Gyroflow?") {
+                        GyroflowPluginBase::open_gyroflow(Some(&target.to_string_lossy()));
+                    }
+                }
+            }
         }
         if param == Params::OpenRecentProject {
             let last_project = gyroflow_core::settings::get_str("lastProject", "");
@@ -949,15 +1198,15 @@ impl GyroflowPluginBaseInstance {
             let ds = params.get_bool(Params::DisableStretch).unwrap_or(false);
             if params.get_bool(Params::IncludeProjectData).unwrap_or_default() {
                 if path.ends_with(".gyroflow") {
-                    if let Ok(data) = std::fs::read_to_string(&path) {
+                    if let Ok(data) = std::fs::read_to_string(path_remap::resolve(&path, &self.media_file_path)) {
                         if StabilizationManager::project_has_motion_data(data.as_bytes()) {
                             let data = GyroflowPluginBase::maybe_inject_disable_stretch(&data, ds);
-                            params.set_string(Params::ProjectData, &data)?;
+                            embedded::set(params, Params::ProjectData, &data)?;
                         } else {
                             if let Some((_, stab)) = self.managers.peek_lru() {
                                 if let Ok(data) = stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
                                     let data = GyroflowPluginBase::maybe_inject_disable_stretch(&data, ds);
-                                    params.set_string(Params::ProjectData, &data)?;
+                                    embedded::set(params, Params::ProjectData, &data)?;
                                 }
                             }
                         }
@@ -968,7 +1217,7 @@ impl GyroflowPluginBaseInstance {
                     if let Some((_, stab)) = self.managers.peek_lru() {
                         if let Ok(data) = stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
                             let data = GyroflowPluginBase::maybe_inject_disable_stretch(&data, ds);
-                            params.set_string(Params::ProjectData, &data)?;
+                            embedded::set(params, Params::ProjectData, &data)?;
                         }
                     }
                 }
@@ -977,6 +1226,7 @@ impl GyroflowPluginBaseInstance {
             }
         }
         if user_edited {
+            overrides::set(params, &[param])?;
             if param == Params::OutputWidth || param == Params::OutputHeight || param == Params::OutputSizeSwap || param == Params::OutputSizeToTimeline {
                 if param == Params::OutputSizeSwap {
                     let (w, h) = (params.get_f64(Params::OutputWidth)?, params.get_f64(Params::OutputHeight)? as _);
@@ -987,7 +1237,7 @@ impl GyroflowPluginBaseInstance {
                     params.set_f64(Params::OutputWidth, self.timeline_size.0 as f64)?;
                     params.set_f64(Params::OutputHeight, self.timeline_size.1 as f64)?;
                 }
-                for (_, v) in self.managers.iter_mut() {
+                for (_, v) in self.managers.iter() {
                     v.set_output_size(params.get_f64(Params::OutputWidth)? as _, params.get_f64(Params::OutputHeight)? as _);
                     v.invalidate_blocking_zooming();
                 }
@@ -995,12 +1245,16 @@ impl GyroflowPluginBaseInstance {
             match param {
                 Params::Fov | Params::Smoothness | Params::ZoomLimit | Params::LensCorrectionStrength |
                 Params::HorizonLockAmount | Params::HorizonLockRoll |
-                //Params::PositionX | Params::PositionY |
+                Params::SmoothingAlgorithm | Params::PerAxis | Params::SmoothnessPitch | Params::SmoothnessYaw | Params::SmoothnessRoll |
+                Params::MaxSmoothness | Params::MaxSmoothnessAtHighVelocity | Params::TimeConstant |
+                Params::PositionX | Params::PositionY | Params::ZoomMode | Params::ZoomSpeed | Params::ZoomMethod |
+                Params::FrameReadoutTime | Params::ReadoutDirection | Params::SyncOffsetMs |
                 Params::AdditionalPitch | Params::AdditionalYaw |
                 Params::Rotation | Params::InputRotation | Params::VideoSpeed | Params::IntegrationMethod |
                 Params::UseGyroflowsKeyframes | Params::RecalculateKeyframes => {
 
                     params.set_string(Params::Status, "Calculating...")?;
+                    self.unsaved_changes = true;
                     if !self.ever_changed {
                         self.ever_changed = true;
                         params.set_string(Params::InstanceId, &format!("{}", fastrand::u64(..)))?;
@@ -1008,7 +1262,7 @@ impl GyroflowPluginBaseInstance {
                     }
                     let use_gyroflows_keyframes = params.get_bool(Params::UseGyroflowsKeyframes).unwrap_or_default();
                     self.cache_keyframes(params, use_gyroflows_keyframes, self.num_frames, self.fps.max(1.0));
-                    for (_, v) in self.managers.iter_mut() {
+                    for (_, v) in self.managers.iter() {
                         match param {
                             Params::IntegrationMethod => {
                                 if let Ok(im) = params.get_i32(Params::IntegrationMethod) {
@@ -1019,12 +1273,31 @@ impl GyroflowPluginBaseInstance {
                                 v.invalidate_blocking_smoothing();
                                 v.invalidate_blocking_zooming();
                             }
+                            Params::SmoothingAlgorithm | Params::PerAxis => {
+                                smoothing::apply(params, v);
+                                v.invalidate_blocking_smoothing();
+                                v.invalidate_blocking_zooming();
+                            }
+                            Params::ZoomMode | Params::ZoomSpeed | Params::ZoomMethod => {
+                                Self::set_zooming(params, v);
+                                v.invalidate_blocking_zooming();
+                            }
+                            Params::FrameReadoutTime | Params::ReadoutDirection => {
+                                Self::set_rolling_shutter(params, v);
+                            }
+                            Params::SyncOffsetMs => {
+                                sync_offset::set_adjustment(v, self.sync_adjustment(params));
+                                v.invalidate_blocking_smoothing();
+                                v.invalidate_blocking_zooming();
+                            }
+                            Params::SmoothnessPitch | Params::SmoothnessYaw | Params::SmoothnessRoll |
+                            Params::MaxSmoothness | Params::MaxSmoothnessAtHighVelocity | Params::TimeConstant |
                             Params::Smoothness | Params::ZoomLimit | Params::HorizonLockAmount | Params::HorizonLockRoll |
                             Params::AdditionalPitch | Params::AdditionalYaw | Params::RecalculateKeyframes => {
                                 v.invalidate_blocking_smoothing();
                                 v.invalidate_blocking_zooming();
                             },
-                            //Params::PositionX | Params::PositionY |
+                            Params::PositionX | Params::PositionY |
                             Params::LensCorrectionStrength | Params::Rotation => {
                                 v.invalidate_blocking_zooming();
                             },
@@ -1039,13 +1312,19 @@ impl GyroflowPluginBaseInstance {
                             _ => { }
                         }
                     }
+                    if param == Params::SmoothingAlgorithm {
+                        smoothing::update_enabled(params, !self.managers.is_empty());
+                    }
+                    if param == Params::ZoomMode {
+                        Self::update_zooming_enabled(params, !self.managers.is_empty());
+                    }
                     params.set_string(Params::Status, "OK")?;
                 },
                 _ => { }
             }
             if param == Params::ToggleOverview {
                 let on = params.get_bool(Params::ToggleOverview)?;
-                for (_, v) in self.managers.iter_mut() {
+                for (_, v) in self.managers.iter() {
                     v.set_fov_overview(on);
                     v.invalidate_blocking_undistortion();
                 }
@@ -1060,6 +1339,22 @@ impl GyroflowPluginBaseInstance {
     }
 }
 
+/// Options of `ZoomMode`
+pub const ZOOM_DISABLED: i32 = 0;
+pub const ZOOM_STATIC:   i32 = 1;
+pub const ZOOM_DYNAMIC:  i32 = 2;
+
+/// `ZoomMode` of the adaptive zoom window from the project: negative disables zooming, 0 is static
+pub fn zoom_mode(adaptive_zoom_window: f64) -> i32 {
+    if adaptive_zoom_window < -0.9 {
+        ZOOM_DISABLED
+    } else if adaptive_zoom_window < 0.0001 {
+        ZOOM_STATIC
+    } else {
+        ZOOM_DYNAMIC
+    }
+}
+
 pub fn hash_string(s: &str) -> u64 {
     use std::hash::{Hash, Hasher};
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
@@ -1078,179 +1373,3 @@ impl ToString for Params {
         format!("{:?}", self)
     }
 }
-
-#[macro_export]
-macro_rules! define_params {
-    ($name:ident {
-        strings: [ $($str_enum:ident  => $str_field:ident: $str_host_type:ty,)* ],
-        bools:   [ $($bool_enum:ident => $bool_field:ident: $bool_host_type:ty,)* ],
-        f64s:    [ $($f64_enum:ident  => $f64_field:ident: $f64_host_type:ty,)* ],
-        i32s:    [ $($i32_enum:ident  => $i32_field:ident: $i32_host_type:ty,)* ],
-
-        get_string:  $gstr_s:ident   $gstr_p:ident                    $gstr_block:block,
-        set_string:  $sstr_s:ident   $sstr_p:ident,   $sstr_v:ident   $sstr_block:block,
-        get_bool:    $gbool_s:ident  $gbool_p:ident                   $gbool_block:block,
-        set_bool:    $sbool_s:ident  $sbool_p:ident,  $sbool_v:ident  $sbool_block:block,
-        get_f64:     $gf64_s:ident   $gf64_p:ident                    $gf64_block:block,
-        set_f64:     $sf64_s:ident   $sf64_p:ident,   $sf64_v:ident   $sf64_block:block,
-        get_i32:     $gi32_s:ident   $gi32_p:ident                    $gi32_block:block,
-        set_i32:     $si32_s:ident   $si32_p:ident,   $si32_v:ident   $si32_block:block,
-        set_label:   $slabel_s:ident $slabel_p:ident, $slabel_v:ident $slabel_block:block,
-        set_hint:    $shint_s:ident  $shint_p:ident,  $shint_v:ident  $shint_block:block,
-        set_enabled: $sen_s:ident    $sen_p:ident,    $sen_v:ident    $sen_block:block,
-        get_bool_at_time: $gtbool_s:ident  $gtbool_p:ident, $gtbool_t:ident                $gtbool_block:block,
-        get_f64_at_time:  $gtf64_s:ident   $gtf64_p:ident,  $gtf64_t:ident                 $gtf64_block:block,
-        set_f64_at_time:  $stf64_s:ident  $stf64_p:ident,  $stf64_t:ident, $stf64_v:ident $stf64_block:block,
-        is_keyframed: $iskeyframe_s:ident  $iskeyframe_p:ident $iskeyframe_block:block,
-        get_keyframes: $gkeyframes_s:ident $gkeyframes_p:ident $gkeyframes_block:block,
-        clear_keyframes: $clr_s:ident      $clr_p:ident $clr_block:block,
-
-        $($additional_fields:ident: $additional_fields_t:ty,)*
-    }) => {
-        #[derive(Default)]
-        pub struct ParamsAdditionalFields {
-            $( pub $additional_fields: $additional_fields_t, )*
-        }
-        pub struct $name {
-            $( $str_field: $str_host_type, )*
-            $( $bool_field: $bool_host_type, )*
-            $( $f64_field: $f64_host_type, )*
-            $( $i32_field: $i32_host_type, )*
-
-            pub fields: ParamsAdditionalFields,
-        }
-        impl GyroflowPluginParams for $name {
-            fn get_string(&self, param: Params) -> $crate::PluginResult<String> {
-                let $gstr_s = &self.fields;
-                match param {
-                    $( Params::$str_enum => { let $gstr_p = &self.$str_field; $gstr_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_string(&mut self, param: Params, value: &str) -> $crate::PluginResult<()> {
-                let mut $sstr_s = &mut self.fields;
-                match param {
-                    $( Params::$str_enum => { let $sstr_p = &mut self.$str_field; let $sstr_v = value; $sstr_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_bool(&self, param: Params) -> $crate::PluginResult<bool> {
-                let $gbool_s = &self.fields;
-                match param {
-                    $( Params::$bool_enum => { let $gbool_p = &self.$bool_field; $gbool_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_bool(&mut self, param: Params, value: bool) -> $crate::PluginResult<()> {
-                let mut $sbool_s = &mut self.fields;
-                match param {
-                    $( Params::$bool_enum => { let $sbool_p = &mut self.$bool_field; let $sbool_v = value; $sbool_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_f64(&self, param: Params) -> $crate::PluginResult<f64> {
-                let $gf64_s = &self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $gf64_p = &self.$f64_field; $gf64_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_f64(&mut self, param: Params, value: f64) -> $crate::PluginResult<()> {
-                let mut $sf64_s = &mut self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $sf64_p = &mut self.$f64_field; let $sf64_v = value; $sf64_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_i32(&self, param: Params) -> $crate::PluginResult<i32> {
-                let $gi32_s = &self.fields;
-                match param {
-                    $( Params::$i32_enum => { let $gi32_p = &self.$i32_field; $gi32_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_i32(&mut self, param: Params, value: i32) -> $crate::PluginResult<()> {
-                let mut $si32_s = &mut self.fields;
-                match param {
-                    $( Params::$i32_enum => { let $si32_p = &mut self.$i32_field; let $si32_v = value; $si32_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_label(&mut self, param: Params, label: &str) -> $crate::PluginResult<()> {
-                let mut $slabel_s = &mut self.fields;
-                let $slabel_v = label;
-                match param {
-                    $( Params::$str_enum  => { let $slabel_p = &mut self.$str_field;  $slabel_block }, )*
-                    $( Params::$bool_enum => { let $slabel_p = &mut self.$bool_field; $slabel_block }, )*
-                    $( Params::$f64_enum  => { let $slabel_p = &mut self.$f64_field;  $slabel_block }, )*
-                    $( Params::$i32_enum  => { let $slabel_p = &mut self.$i32_field;  $slabel_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_hint(&mut self, param: Params, hint: &str) -> $crate::PluginResult<()> {
-                let mut $shint_s = &mut self.fields;
-                let $shint_v = hint;
-                match param {
-                    $( Params::$str_enum  => { let $shint_p = &mut self.$str_field;  $shint_block }, )*
-                    $( Params::$bool_enum => { let $shint_p = &mut self.$bool_field; $shint_block }, )*
-                    $( Params::$f64_enum  => { let $shint_p = &mut self.$f64_field;  $shint_block }, )*
-                    $( Params::$i32_enum  => { let $shint_p = &mut self.$i32_field;  $shint_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_enabled(&mut self, param: Params, enabled: bool) -> $crate::PluginResult<()> {
-                let mut $sen_s = &mut self.fields;
-                let $sen_v = enabled;
-                match param {
-                    $( Params::$str_enum  => { let $sen_p = &mut self.$str_field;  $sen_block }, )*
-                    $( Params::$bool_enum => { let $sen_p = &mut self.$bool_field; $sen_block }, )*
-                    $( Params::$f64_enum  => { let $sen_p = &mut self.$f64_field;  $sen_block }, )*
-                    $( Params::$i32_enum  => { let $sen_p = &mut self.$i32_field;  $sen_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_f64_at_time(&self, param: Params, time: TimeType) -> $crate::PluginResult<f64> {
-                let $gtf64_s = &self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $gtf64_p = &self.$f64_field; let $gtf64_t = time; $gtf64_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_bool_at_time(&self, param: Params, time: TimeType) -> $crate::PluginResult<bool> {
-                let $gtbool_s = &self.fields;
-                match param {
-                    $( Params::$bool_enum => { let $gtbool_p = &self.$bool_field; let $gtbool_t = time; $gtbool_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn clear_keyframes(&mut self, param: Params) -> $crate::PluginResult<()> {
-                let mut $clr_s = &mut self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $clr_p = &mut self.$f64_field; $clr_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn is_keyframed(&self, param: Params) -> bool {
-                let $iskeyframe_s = &self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $iskeyframe_p = &self.$f64_field; $iskeyframe_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn get_keyframes(&self, param: Params) -> Vec<(TimeType, f64)> {
-                let $gkeyframes_s = &self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $gkeyframes_p = &self.$f64_field; $gkeyframes_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-            fn set_f64_at_time(&mut self, param: Params, time: TimeType, value: f64) -> $crate::PluginResult<()> {
-                let mut $stf64_s = &mut self.fields;
-                match param {
-                    $( Params::$f64_enum => { let $stf64_p = &mut self.$f64_field; let $stf64_t = time; let $stf64_v = value; $stf64_block }, )*
-                    _ => panic!("Wrong parameter type"),
-                }
-            }
-        }
-    };
-}
@@ -0,0 +1,178 @@
+// Loads projects and video files in a background thread.
+// Parsing the gyro data can take several seconds, so hosts render pass-through frames in the viewer until the worker finishes,
+// and the parameters are updated from the result on the next render call.
+
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, AtomicU64, Ordering::SeqCst };
+use std::thread::JoinHandle;
+use gyroflow_core::{ StabilizationManager, filesystem };
+use crate::{ GyroflowPluginBase, PluginError };
+
+/// Parameter values needed to load the project, read on the host thread
+pub struct LoadRequest {
+    pub path: String,
+    pub out_size: (usize, usize),
+    pub embedded_lens: String,
+    pub embedded_preset: String,
+    pub project_data: String,
+    pub include_project_data: bool,
+    pub disable_stretch: bool,
+    pub read_video_rotation: bool,
+}
+
+/// Result of the loading, applied to the parameters on the host thread
+pub struct LoadedManager {
+    pub stab: StabilizationManager,
+    /// Path the project was loaded from, after `path_remap::resolve`
+    pub path: String,
+    pub is_video_file: bool,
+    /// Value to store in `Params::ProjectData`
+    pub project_data: Option<String>,
+    /// Rotation from the video metadata
+    pub video_rotation: Option<i32>,
+    pub set_loaded_project: bool,
+    /// Non-fatal problems with the embedded lens profile or preset
+    pub warnings: Vec<PluginError>,
+}
+
+pub struct PendingLoad {
+    pub key: String,
+    progress: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Result<LoadedManager, PluginError>>>,
+    error: Option<PluginError>,
+}
+
+impl PendingLoad {
+    pub fn start(key: String, request: LoadRequest, stab: StabilizationManager) -> Self {
+        let progress = Arc::new(AtomicU64::new(0f64.to_bits()));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let (progress, cancel) = (progress.clone(), cancel.clone());
+            std::thread::spawn(move || load(&request, stab, progress, cancel))
+        };
+        Self { key, progress, cancel, handle: Some(handle), error: None }
+    }
+
+    /// Loading progress, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        f64::from_bits(self.progress.load(SeqCst))
+    }
+
+    /// Whether the loading finished with an error
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns `None` while the worker is running. A failed load keeps its error until the load is restarted
+    pub fn poll(&mut self) -> Option<Result<LoadedManager, PluginError>> {
+        if self.error.is_none() && !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        self.wait()
+    }
+
+    /// Blocks until the worker finishes, returns `None` if the result was already taken
+    pub fn wait(&mut self) -> Option<Result<LoadedManager, PluginError>> {
+        if let Some(e) = &self.error {
+            return Some(Err(e.clone()));
+        }
+        let result = self.handle.take()?.join().unwrap_or_else(|_| Err(PluginError::ProjectInvalid { path: self.key.clone(), reason: "Loading thread panicked".into() }));
+        if let Err(e) = &result {
+            self.error = Some(e.clone());
+        }
+        Some(result)
+    }
+}
+
+impl Drop for PendingLoad {
+    fn drop(&mut self) {
+        self.cancel.store(true, SeqCst);
+    }
+}
+
+/// Loads the project synchronously, `progress` is updated with f64 bits of the progress from 0.0 to 1.0
+pub fn load(req: &LoadRequest, mut stab: StabilizationManager, progress: Arc<AtomicU64>, cancel: Arc<AtomicBool>) -> Result<LoadedManager, PluginError> {
+    crate::lens_db::ensure_loaded();
+
+    let progress_cb = move |p: f64| progress.store(p.to_bits(), SeqCst);
+    let mut is_preset = false;
+
+    let is_video_file = !req.path.ends_with(".gyroflow");
+    let mut project_data = None;
+    let mut video_rotation = None;
+    let mut set_loaded_project = false;
+    let mut warnings = Vec::new();
+
+    if is_video_file {
+        let url = filesystem::path_to_url(&req.path);
+        let mut file = filesystem::open_file(&url, false, false).map_err(|e| {
+            log::error!("Failed to open {url}: {e:?}");
+            PluginError::ProjectNotFound(req.path.clone())
+        })?;
+        let filesize = file.size;
+        match stab.load_video_file(file.get_file(), filesize, &url, None, true) {
+            Ok(md) => {
+                if req.out_size != (0, 0) {
+                    stab.params.write().output_size = req.out_size; // Default to timeline output size
+                }
+                if let Some(preset_out_size) = stab.input_file.read().preset_output_size {
+                    stab.params.write().output_size = preset_out_size;
+                }
+                if !req.embedded_lens.is_empty() {
+                    if let Err(e) = stab.load_lens_profile(&req.embedded_lens) {
+                        warnings.push(PluginError::LensProfileInvalid(format!("{e:?}")));
+                    }
+                }
+                if !req.embedded_preset.is_empty() {
+                    if let Err(e) = stab.import_gyroflow_data(req.embedded_preset.as_bytes(), true, None, |_|(), cancel.clone(), &mut is_preset, true) {
+                        warnings.push(PluginError::PresetInvalid(format!("{e:?}")));
+                    }
+                }
+                if req.include_project_data {
+                    if let Ok(data) = stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
+                        project_data = Some(GyroflowPluginBase::maybe_inject_disable_stretch(&data, req.disable_stretch));
+                    }
+                }
+                video_rotation = Some(md.rotation);
+                set_loaded_project = true;
+            },
+            Err(e) => {
+                if req.project_data.is_empty() {
+                    return Err(PluginError::VideoUnreadable { path: req.path.clone(), reason: format!("{e:?}") });
+                }
+                stab.import_gyroflow_data(req.project_data.as_bytes(), true, None, progress_cb, cancel, &mut is_preset, true)
+                    .map_err(|e| PluginError::ProjectInvalid { path: req.path.clone(), reason: e.to_string() })?;
+            }
+        }
+    } else {
+        let data = if req.include_project_data && !req.project_data.is_empty() {
+            req.project_data.clone()
+        } else if let Ok(data) = std::fs::read_to_string(&req.path) {
+            project_data = Some(if req.include_project_data { data.clone() } else { String::new() });
+            data
+        } else {
+            String::new()
+        };
+        stab.import_gyroflow_data(data.as_bytes(), true, Some(&filesystem::path_to_url(&req.path)), progress_cb, cancel, &mut is_preset, true).map_err(|e| {
+            if data.is_empty() {
+                PluginError::ProjectNotFound(req.path.clone())
+            } else {
+                PluginError::ProjectInvalid { path: req.path.clone(), reason: e.to_string() }
+            }
+        })?;
+        set_loaded_project = true;
+
+        if req.read_video_rotation {
+            let url = stab.input_file.read().url.clone();
+            if let Ok(mut file) = filesystem::open_file(&url, false, false) {
+                let filesize = file.size;
+                if let Ok(video_md) = gyroflow_core::util::get_video_metadata(file.get_file(), filesize, &url) {
+                    video_rotation = Some(video_md.rotation);
+                }
+            }
+        }
+    }
+
+    Ok(LoadedManager { stab, path: req.path.clone(), is_video_file, project_data, video_rotation, set_loaded_project, warnings })
+}
@@ -0,0 +1,198 @@
+// In-memory implementation of `GyroflowPluginParams`, acting as a stand-in for the video editor.
+// It allows running the whole plugin flow (loading, parameter changes, keyframes) without a host application,
+// which is useful for tests and for headless tools.
+
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use crate::{ GyroflowPluginParams, ParamDefinition, ParameterType, Params, PluginResult, TimeType };
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    String(String),
+    Bool(bool),
+    F64(f64),
+    I32(i32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamChange {
+    Value(Params, ParamValue),
+    Label(Params, String),
+    Hint(Params, String),
+    Enabled(Params, bool),
+    Keyframe(Params, i64, f64),
+    ClearKeyframes(Params),
+}
+
+pub struct MemoryParams {
+    values:    HashMap<Params, ParamValue>,
+    labels:    HashMap<Params, String>,
+    hints:     HashMap<Params, String>,
+    disabled:  HashSet<Params>,
+    keyframes: HashMap<Params, BTreeMap<i64, f64>>, // timestamp_us -> value
+
+    /// Frame rate used to convert frame numbers to timestamps
+    pub fps: f64,
+    /// Every change made through the `GyroflowPluginParams` interface, in order
+    pub changes: Vec<ParamChange>,
+}
+
+impl Default for MemoryParams {
+    fn default() -> Self {
+        let mut ret = Self {
+            values:    HashMap::new(),
+            labels:    HashMap::new(),
+            hints:     HashMap::new(),
+            disabled:  HashSet::new(),
+            keyframes: HashMap::new(),
+            fps:       30.0,
+            changes:   Vec::new(),
+        };
+        for def in ParamDefinition::all() {
+            if !def.label.is_empty() { ret.labels.insert(def.param, def.label.to_owned()); }
+            if !def.hint.is_empty()  { ret.hints.insert(def.param, def.hint.to_owned()); }
+            let value = match def.typ {
+                ParameterType::HiddenString | ParameterType::TextBox | ParameterType::Text => ParamValue::String(String::new()),
+                ParameterType::Slider   { default, .. } => ParamValue::F64(default),
+                ParameterType::Checkbox { default }     => ParamValue::Bool(default),
+                ParameterType::Select   { .. }          => ParamValue::I32(def.default_index()),
+                ParameterType::DynamicSelect { .. }     => ParamValue::I32(0),
+                ParameterType::Button | ParameterType::Group { .. } => continue,
+            };
+            ret.values.insert(def.param, value);
+        }
+        ret
+    }
+}
+
+impl MemoryParams {
+    pub fn new(fps: f64) -> Self {
+        Self { fps, ..Default::default() }
+    }
+
+    pub fn timestamp_us(&self, time: &TimeType) -> i64 {
+        match *time {
+            TimeType::FrameOrMicrosecond((Some(f), None)) |
+            TimeType::Frame(f) => (f / self.fps * 1_000_000.0).round() as i64,
+            TimeType::Milliseconds(ms) => (ms * 1_000.0).round() as i64,
+            TimeType::Microseconds(us) => us,
+            TimeType::FrameOrMicrosecond((_, Some(us))) => us,
+            TimeType::FrameOrMicrosecond((None, None)) => 0,
+        }
+    }
+
+    pub fn value(&self, param: Params) -> Option<&ParamValue> { self.values.get(&param) }
+    pub fn label(&self, param: Params) -> Option<&str> { self.labels.get(&param).map(String::as_str) }
+    pub fn hint(&self, param: Params) -> Option<&str> { self.hints.get(&param).map(String::as_str) }
+    pub fn is_enabled(&self, param: Params) -> bool { !self.disabled.contains(&param) }
+
+    /// Returns all changes recorded so far and clears the list
+    pub fn take_changes(&mut self) -> Vec<ParamChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    fn get(&self, param: Params) -> PluginResult<&ParamValue> {
+        self.values.get(&param).ok_or_else(|| format!("Parameter {param:?} doesn't have a value").into())
+    }
+    fn set(&mut self, param: Params, value: ParamValue) -> PluginResult<()> {
+        if let Some(current) = self.values.get(&param) {
+            if std::mem::discriminant(current) != std::mem::discriminant(&value) {
+                return Err(format!("Wrong parameter type for {param:?}: {value:?}").into());
+            }
+        }
+        self.changes.push(ParamChange::Value(param, value.clone()));
+        self.values.insert(param, value);
+        Ok(())
+    }
+}
+
+impl GyroflowPluginParams for MemoryParams {
+    fn set_enabled(&mut self, param: Params, enabled: bool) -> PluginResult<()> {
+        if enabled { self.disabled.remove(&param); } else { self.disabled.insert(param); }
+        self.changes.push(ParamChange::Enabled(param, enabled));
+        Ok(())
+    }
+    fn set_label(&mut self, param: Params, label: &str) -> PluginResult<()> {
+        self.labels.insert(param, label.to_owned());
+        self.changes.push(ParamChange::Label(param, label.to_owned()));
+        Ok(())
+    }
+    fn set_hint(&mut self, param: Params, hint: &str) -> PluginResult<()> {
+        self.hints.insert(param, hint.to_owned());
+        self.changes.push(ParamChange::Hint(param, hint.to_owned()));
+        Ok(())
+    }
+
+    fn set_f64(&mut self, param: Params, value: f64) -> PluginResult<()> {
+        self.set(param, ParamValue::F64(value))
+    }
+    fn get_f64(&self, param: Params) -> PluginResult<f64> {
+        match self.get(param)? {
+            ParamValue::F64(v) => Ok(*v),
+            _ => Err(format!("Wrong parameter type for {param:?}").into())
+        }
+    }
+    fn get_f64_at_time(&self, param: Params, time: TimeType) -> PluginResult<f64> {
+        let Some(keys) = self.keyframes.get(&param).filter(|x| !x.is_empty()) else {
+            return self.get_f64(param);
+        };
+        let ts = self.timestamp_us(&time);
+        let prev = keys.range(..=ts).next_back();
+        let next = keys.range(ts..).next();
+        Ok(match (prev, next) {
+            (Some((t1, v1)), Some((t2, v2))) if t2 > t1 => v1 + (v2 - v1) * ((ts - t1) as f64 / (t2 - t1) as f64),
+            (Some((_, v)), _) | (None, Some((_, v))) => *v,
+            (None, None) => unreachable!(),
+        })
+    }
+    fn set_bool(&mut self, param: Params, value: bool) -> PluginResult<()> {
+        self.set(param, ParamValue::Bool(value))
+    }
+    fn get_bool(&self, param: Params) -> PluginResult<bool> {
+        match self.get(param)? {
+            ParamValue::Bool(v) => Ok(*v),
+            _ => Err(format!("Wrong parameter type for {param:?}").into())
+        }
+    }
+    fn get_bool_at_time(&self, param: Params, _time: TimeType) -> PluginResult<bool> {
+        self.get_bool(param)
+    }
+    fn set_string(&mut self, param: Params, value: &str) -> PluginResult<()> {
+        self.set(param, ParamValue::String(value.to_owned()))
+    }
+    fn get_string(&self, param: Params) -> PluginResult<String> {
+        match self.get(param)? {
+            ParamValue::String(v) => Ok(v.clone()),
+            _ => Err(format!("Wrong parameter type for {param:?}").into())
+        }
+    }
+    fn set_i32(&mut self, param: Params, value: i32) -> PluginResult<()> {
+        self.set(param, ParamValue::I32(value))
+    }
+    fn get_i32(&self, param: Params) -> PluginResult<i32> {
+        match self.get(param)? {
+            ParamValue::I32(v) => Ok(*v),
+            _ => Err(format!("Wrong parameter type for {param:?}").into())
+        }
+    }
+
+    fn is_keyframed(&self, param: Params) -> bool {
+        self.keyframes.get(&param).map(|x| !x.is_empty()).unwrap_or_default()
+    }
+    fn get_keyframes(&self, param: Params) -> Vec<(TimeType, f64)> {
+        self.keyframes.get(&param)
+            .map(|keys| keys.iter().map(|(t, v)| (TimeType::Microseconds(*t), *v)).collect())
+            .unwrap_or_default()
+    }
+    fn clear_keyframes(&mut self, param: Params) -> PluginResult<()> {
+        self.keyframes.remove(&param);
+        self.changes.push(ParamChange::ClearKeyframes(param));
+        Ok(())
+    }
+    fn set_f64_at_time(&mut self, param: Params, time: TimeType, value: f64) -> PluginResult<()> {
+        self.get_f64(param)?; // Only numeric parameters can be keyframed
+        let ts = self.timestamp_us(&time);
+        self.keyframes.entry(param).or_default().insert(ts, value);
+        self.changes.push(ParamChange::Keyframe(param, ts, value));
+        Ok(())
+    }
+}
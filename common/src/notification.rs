@@ -0,0 +1,131 @@
+// Messages for the user which aren't tied to the `Status` parameter.
+// Modal dialogs block forever on render nodes and in automated pipelines, so every message goes through a sink,
+// which can be selected by the host with `set_sink`, or with the `GYROFLOW_PLUGIN_NOTIFICATIONS` environment variable.
+
+use std::sync::{ Arc, OnceLock };
+use parking_lot::RwLock;
+use crate::{ GyroflowPluginParams, Params, PluginError, Severity };
+
+/// Environment variable selecting the default sink: `dialog`, `log` or `status`. Defaults to `log` in headless mode
+pub const SINK_ENV_VAR: &str = "GYROFLOW_PLUGIN_NOTIFICATIONS";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: Option<String>,
+    pub message: String,
+    pub severity: Severity,
+}
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self { title: None, message: message.into(), severity }
+    }
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+impl From<&PluginError> for Notification {
+    fn from(e: &PluginError) -> Self {
+        Self::new(e.severity(), e.hint()).with_title(e.status())
+    }
+}
+
+pub trait NotificationSink: Send + Sync {
+    /// `params` is `None` when the message isn't related to any instance, eg. from a background thread
+    fn notify(&self, params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification);
+    /// Shows the message with a yes/no `question`. Sinks which can't ask only show the message, and the answer is no
+    fn ask(&self, params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification, _question: &str) -> bool {
+        self.notify(params, notification);
+        false
+    }
+}
+
+/// Shows a modal dialog, for interactive use. Only logs in headless mode
+pub struct DialogSink;
+impl DialogSink {
+    fn dialog(notification: &Notification, description: &str) -> rfd::MessageDialog {
+        let d = rfd::MessageDialog::new()
+            .set_description(description)
+            .set_level(match notification.severity {
+                Severity::Info    => rfd::MessageLevel::Info,
+                Severity::Warning => rfd::MessageLevel::Warning,
+                Severity::Error   => rfd::MessageLevel::Error,
+            });
+        match &notification.title {
+            Some(title) => d.set_title(title),
+            None => d
+        }
+    }
+}
+impl NotificationSink for DialogSink {
+    fn notify(&self, _params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification) {
+        LogSink.notify(None, notification);
+        if crate::headless::is_headless() { return; }
+        Self::dialog(notification, &notification.message).show();
+    }
+    fn ask(&self, _params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification, question: &str) -> bool {
+        LogSink.notify(None, notification);
+        if crate::headless::is_headless() { return false; }
+        Self::dialog(notification, &format!("{}\n{question}", notification.message))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show() == rfd::MessageDialogResult::Yes
+    }
+}
+
+/// Only writes the message to the log
+pub struct LogSink;
+impl NotificationSink for LogSink {
+    fn notify(&self, _params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification) {
+        let text = match &notification.title {
+            Some(title) => format!("{title}: {}", notification.message),
+            None        => notification.message.clone(),
+        };
+        match notification.severity {
+            Severity::Info    => log::info!("{text}"),
+            Severity::Warning => log::warn!("{text}"),
+            Severity::Error   => log::error!("{text}"),
+        }
+    }
+}
+
+/// Displays the message in the `Status` parameter, with the details in its tooltip. Messages without parameters are logged
+pub struct StatusSink;
+impl NotificationSink for StatusSink {
+    fn notify(&self, params: Option<&mut dyn GyroflowPluginParams>, notification: &Notification) {
+        LogSink.notify(None, notification);
+        if let Some(params) = params {
+            let _ = params.set_string(Params::Status, notification.title.as_deref().unwrap_or(&notification.message));
+            let _ = params.set_hint(Params::Status, &notification.message);
+        }
+    }
+}
+
+fn sink() -> &'static RwLock<Arc<dyn NotificationSink>> {
+    static SINK: OnceLock<RwLock<Arc<dyn NotificationSink>>> = OnceLock::new();
+    SINK.get_or_init(|| {
+        let sink: Arc<dyn NotificationSink> = match std::env::var(SINK_ENV_VAR).unwrap_or_default().to_ascii_lowercase().as_str() {
+            "log"    => Arc::new(LogSink),
+            "status" => Arc::new(StatusSink),
+            _ if crate::headless::is_headless() => Arc::new(LogSink),
+            _        => Arc::new(DialogSink),
+        };
+        RwLock::new(sink)
+    })
+}
+
+/// Replaces the sink used by `notify` in this process
+pub fn set_sink(new_sink: Arc<dyn NotificationSink>) {
+    *sink().write() = new_sink;
+}
+
+pub fn notify(params: Option<&mut dyn GyroflowPluginParams>, notification: Notification) {
+    // Clone the sink, so it can show a modal dialog without holding the lock
+    let sink = sink().read().clone();
+    sink.notify(params, &notification);
+}
+
+/// Like `notify`, with a yes/no question. Returns false when the sink can't ask
+pub fn ask(params: Option<&mut dyn GyroflowPluginParams>, notification: Notification, question: &str) -> bool {
+    let sink = sink().read().clone();
+    sink.ask(params, &notification, question)
+}
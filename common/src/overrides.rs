@@ -0,0 +1,40 @@
+// Parameters added after instances could already be saved in the host's project.
+// Such instances have the default values of these parameters, which mustn't replace the values of the Gyroflow project.
+// So the values are applied to the manager only after they were read from the project or set by the user,
+// which is recorded in the hidden `ProjectOverrides` parameter.
+
+use crate::{ GyroflowPluginParams, ParamDefinition, Params, PluginResult };
+
+/// Parameters which override the project only once they were read from it or set by the user
+pub const PARAMS: &[Params] = &[
+    Params::SmoothingAlgorithm,
+    Params::ZoomMode, Params::ZoomSpeed, Params::ZoomMethod,
+    Params::FrameReadoutTime, Params::ReadoutDirection,
+];
+
+fn ids(params: &dyn GyroflowPluginParams) -> Vec<String> {
+    let value = params.get_string(Params::ProjectOverrides).unwrap_or_default();
+    value.split(',').filter(|x| !x.is_empty()).map(str::to_owned).collect()
+}
+
+/// Whether the value of the parameter is applied to the manager
+pub fn is_set(params: &dyn GyroflowPluginParams, param: Params) -> bool {
+    if !PARAMS.contains(&param) { return true; }
+    let Some(def) = ParamDefinition::find(param) else { return false; };
+    ids(params).iter().any(|x| x == def.id)
+}
+
+/// Records that the parameters were read from the project or set by the user
+pub fn set(params: &mut dyn GyroflowPluginParams, changed: &[Params]) -> PluginResult<()> {
+    let mut ids = ids(params);
+    let count = ids.len();
+    for def in changed.iter().filter(|x| PARAMS.contains(x)).filter_map(|x| ParamDefinition::find(*x)) {
+        if !ids.iter().any(|x| x == def.id) {
+            ids.push(def.id.to_owned());
+        }
+    }
+    if ids.len() != count {
+        params.set_string(Params::ProjectOverrides, &ids.join(","))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// Resolves project paths saved on another machine, eg. a Windows workstation, on the machine which renders the project.
+// Prefix substitution rules are read from `path_mappings.json` in the gyroflow data directory, or from the file
+// in the `GYROFLOW_PLUGIN_PATH_MAPPINGS` environment variable:
+// [ { "from": "D:/Projects/", "to": "/mnt/projects/" } ]
+// When no rule gives an existing file, the project is looked for next to the media file.
+
+use std::path::{ Path, PathBuf };
+use std::sync::OnceLock;
+
+/// Environment variable with the path to the rules file, takes precedence over the file in the data directory
+pub const MAPPINGS_ENV_VAR: &str = "GYROFLOW_PLUGIN_PATH_MAPPINGS";
+pub const MAPPINGS_FILE: &str = "path_mappings.json";
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+pub fn mappings_file() -> PathBuf {
+    match std::env::var(MAPPINGS_ENV_VAR) {
+        Ok(v) if !v.is_empty() => PathBuf::from(v),
+        _ => gyroflow_core::settings::data_dir().join(MAPPINGS_FILE)
+    }
+}
+
+/// Rules from `mappings_file`, the longest prefix first
+pub fn mappings() -> &'static [PathMapping] {
+    static MAPPINGS: OnceLock<Vec<PathMapping>> = OnceLock::new();
+    MAPPINGS.get_or_init(|| {
+        let path = mappings_file();
+        let Ok(data) = std::fs::read_to_string(&path) else { return Vec::new(); };
+        match serde_json::from_str::<Vec<PathMapping>>(&data) {
+            Ok(mut mappings) => {
+                mappings.sort_by_key(|x| std::cmp::Reverse(x.from.len()));
+                log::info!("Loaded {} path mappings from {path:?}", mappings.len());
+                mappings
+            },
+            Err(e) => {
+                log::error!("Invalid path mappings in {path:?}: {e:?}");
+                Vec::new()
+            }
+        }
+    })
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Applies the first matching rule. Prefixes are compared case-insensitively and with any kind of slashes
+pub fn apply(path: &str, mappings: &[PathMapping]) -> Option<String> {
+    let path = normalize(path);
+    mappings.iter().find_map(|m| {
+        let from = normalize(&m.from);
+        let prefix = path.get(..from.len())?;
+        if from.is_empty() || !prefix.eq_ignore_ascii_case(&from) { return None; }
+        Some(format!("{}{}", normalize(&m.to), &path[from.len()..]))
+    })
+}
+
+/// Returns the path of the existing file for `path`, or `path` if it couldn't be found.
+/// `media_path` is the path of the clip the effect is applied to, if known
+pub fn resolve(path: &str, media_path: &str) -> String {
+    resolve_with(path, media_path, mappings())
+}
+
+pub fn resolve_with(path: &str, media_path: &str, mappings: &[PathMapping]) -> String {
+    if path.is_empty() || Path::new(path).exists() {
+        return path.to_owned();
+    }
+    if let Some(mapped) = apply(path, mappings).filter(|x| Path::new(x).exists()) {
+        log::info!("Project path {path} remapped to {mapped}");
+        return mapped;
+    }
+    if !media_path.is_empty() {
+        // The media file may have been moved or remapped as well
+        let media_path = if Path::new(media_path).exists() { Some(media_path.to_owned()) } else { apply(media_path, mappings) };
+        let file_name = normalize(path).rsplit('/').next().map(str::to_owned);
+        if let (Some(media_path), Some(file_name)) = (media_path, file_name) {
+            if let Some(candidate) = Path::new(&media_path).parent().map(|x| x.join(&file_name)).filter(|x| x.exists()) {
+                let candidate = candidate.to_string_lossy().to_string();
+                log::info!("Project path {path} found next to the media file: {candidate}");
+                return candidate;
+            }
+        }
+    }
+    path.to_owned()
+}
@@ -0,0 +1,150 @@
+// Presets with the values of the adjustment parameters, in the format of the presets saved by the Gyroflow app,
+// so they can be shared between the plugins and the app.
+// Saved presets are listed in the `LoadPreset` parameter. Hosts read the list when they register the parameters.
+
+use std::path::{ Path, PathBuf };
+use std::sync::OnceLock;
+use crate::{ GyroflowPluginBase, GyroflowPluginParams, ParamDefinition, Params, PluginResult };
+
+/// Subdirectory of the Gyroflow data directory
+pub const PRESETS_DIR: &str = "presets";
+pub const EXTENSION: &str = "gyroflow";
+/// First option of `LoadPreset`, which doesn't load anything
+pub const NO_PRESET: &str = "Select preset...";
+
+pub fn presets_dir() -> PathBuf {
+    gyroflow_core::settings::data_dir().join(PRESETS_DIR)
+}
+
+/// Presets in `dir` as name and path, sorted by name
+pub fn list_in(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new(); };
+    let mut presets = entries.flatten().map(|x| x.path())
+        .filter(|x| x.is_file() && x.extension().is_some_and(|e| e.eq_ignore_ascii_case(EXTENSION)))
+        .filter_map(|x| Some((x.file_stem()?.to_string_lossy().to_string(), x)))
+        .collect::<Vec<_>>();
+    presets.sort_by_key(|x| x.0.to_lowercase());
+    presets
+}
+pub fn list() -> Vec<(String, PathBuf)> {
+    list_in(&presets_dir())
+}
+
+/// Presets listed in `LoadPreset`. Hosts read the options only once, so the list is kept for the selected index
+/// to match the option, also after more presets are saved
+pub fn listed() -> &'static [(String, PathBuf)] {
+    static LISTED: OnceLock<Vec<(String, PathBuf)>> = OnceLock::new();
+    LISTED.get_or_init(list)
+}
+
+/// Options of `LoadPreset`
+pub fn options() -> Vec<String> {
+    std::iter::once(NO_PRESET.to_owned()).chain(listed().iter().map(|x| x.0.clone())).collect()
+}
+
+fn option_name(param: Params, index: i32) -> Option<String> {
+    ParamDefinition::find(param)?.options().get(index.max(0) as usize).cloned()
+}
+fn option_index(param: Params, name: &str) -> Option<i32> {
+    ParamDefinition::find(param)?.options().iter().position(|x| x.eq_ignore_ascii_case(name)).map(|x| x as i32)
+}
+
+/// Preset with the current values of the parameters
+pub fn from_params(params: &dyn GyroflowPluginParams, name: &str) -> PluginResult<serde_json::Value> {
+    let (method, smoothing_params) = crate::smoothing::to_project(params)?;
+    let preset = serde_json::json!({
+        "title": "Gyroflow preset",
+        "version": 3,
+        "name": name,
+        "stabilization": {
+            "fov":                    params.get_f64(Params::Fov)?,
+            "method":                 method,
+            "smoothing_params":       smoothing_params,
+            "max_zoom":               params.get_f64(Params::ZoomLimit)?,
+            "adaptive_zoom_window":   match params.get_i32(Params::ZoomMode)? { crate::ZOOM_DISABLED => -1.0, crate::ZOOM_STATIC => 0.0, _ => params.get_f64(Params::ZoomSpeed)? },
+            "adaptive_zoom_method":   params.get_i32(Params::ZoomMethod)?,
+            "lens_correction_amount": params.get_f64(Params::LensCorrectionStrength)? / 100.0,
+            "horizon_lock_amount":    params.get_f64(Params::HorizonLockAmount)?,
+            "horizon_lock_roll":      params.get_f64(Params::HorizonLockRoll)?,
+            "additional_rotation":    [ params.get_f64(Params::AdditionalYaw)?, params.get_f64(Params::AdditionalPitch)?, 0.0 ],
+            "video_rotation":         params.get_f64(Params::Rotation)?,
+            "interpolation":          option_name(Params::Interpolation, params.get_i32(Params::Interpolation)?),
+        },
+        "gyro_source": {
+            "integration_method": params.get_i32(Params::IntegrationMethod)?
+        }
+    });
+    let data = GyroflowPluginBase::maybe_inject_disable_stretch(&preset.to_string(), params.get_bool(Params::DisableStretch).unwrap_or_default());
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Sets the parameters from the values in the preset, returns the changed parameters.
+/// Values missing in the preset, for example in presets from the app which only store the lens options, are unchanged
+pub fn apply(params: &mut dyn GyroflowPluginParams, preset: &serde_json::Value) -> PluginResult<Vec<Params>> {
+    let stab = &preset["stabilization"];
+    let mut changed = crate::smoothing::from_project(params, stab["method"].as_str(), stab.get("smoothing_params"))?;
+
+    let values = [
+        (Params::Fov,                    stab["fov"].as_f64()),
+        (Params::ZoomLimit,              stab["max_zoom"].as_f64()),
+        (Params::LensCorrectionStrength, stab["lens_correction_amount"].as_f64().map(|x| (x * 100.0).min(100.0))),
+        (Params::HorizonLockAmount,      stab["horizon_lock_amount"].as_f64()),
+        (Params::HorizonLockRoll,        stab["horizon_lock_roll"].as_f64()),
+        (Params::AdditionalYaw,          stab["additional_rotation"][0].as_f64()),
+        (Params::AdditionalPitch,        stab["additional_rotation"][1].as_f64()),
+        (Params::Rotation,               stab["video_rotation"].as_f64()),
+    ];
+    for (param, value) in values {
+        if let Some(value) = value {
+            params.set_f64(param, value)?;
+            changed.push(param);
+        }
+    }
+    if let Some(window) = stab["adaptive_zoom_window"].as_f64() {
+        params.set_i32(Params::ZoomMode, crate::zoom_mode(window))?;
+        changed.push(Params::ZoomMode);
+        if window > 0.0 {
+            params.set_f64(Params::ZoomSpeed, window)?;
+            changed.push(Params::ZoomSpeed);
+        }
+    }
+    if let Some(method) = stab["adaptive_zoom_method"].as_i64() {
+        params.set_i32(Params::ZoomMethod, method as i32)?;
+        changed.push(Params::ZoomMethod);
+    }
+    if let Some(method) = preset["gyro_source"]["integration_method"].as_i64() {
+        params.set_i32(Params::IntegrationMethod, method as i32)?;
+        changed.push(Params::IntegrationMethod);
+    }
+    if let Some(index) = stab["interpolation"].as_str().and_then(|x| option_index(Params::Interpolation, x)) {
+        params.set_i32(Params::Interpolation, index)?;
+        changed.push(Params::Interpolation);
+    }
+    if preset["plugin_disable_stretch"].as_bool().unwrap_or_default() {
+        params.set_bool(Params::DisableStretch, true)?;
+        changed.push(Params::DisableStretch);
+    }
+    Ok(changed)
+}
+
+/// Writes the preset to `path`, adding the extension if it's missing
+pub fn save(preset: &serde_json::Value, path: &Path) -> PluginResult<PathBuf> {
+    let path = if path.extension().is_some_and(|e| e.eq_ignore_ascii_case(EXTENSION)) { path.to_path_buf() } else { path.with_extension(EXTENSION) };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(preset)?)?;
+    log::info!("Saved preset to {path:?}");
+    Ok(path)
+}
+
+/// Reads the preset file, returns the name and the preset data
+pub fn load(path: &Path) -> PluginResult<(String, String)> {
+    let data = std::fs::read_to_string(path)?;
+    let preset: serde_json::Value = serde_json::from_str(&data)?;
+    let name = match preset["name"].as_str() {
+        Some(name) if !name.is_empty() => name.to_owned(),
+        _ => path.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_default()
+    };
+    Ok((name, data))
+}
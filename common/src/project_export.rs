@@ -0,0 +1,99 @@
+// Writes the values rendered by the plugin back into the Gyroflow project.
+// The plugin parameters and the editor keyframes only reach the manager through the keyframe provider,
+// so without merging them, the project opened in the app doesn't show what the editor renders.
+// The values are merged into a copy of the manager, and saved next to the project instead of overwriting it.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::keyframes::{ KeyframeManager, KeyframeType };
+use crate::PluginResult;
+
+/// Added to the name of the project saved by the plugin
+pub const SUFFIX: &str = "_plugin";
+
+fn set_static_value(stab: &StabilizationManager, typ: &KeyframeType, value: f64) {
+    match typ {
+        KeyframeType::Fov                      => stab.params.write().fov = value,
+        KeyframeType::MaxZoom                  => stab.params.write().max_zoom = Some(value),
+        KeyframeType::LensCorrectionStrength   => stab.params.write().lens_correction_amount = value,
+        KeyframeType::AdditionalRotationX      => stab.params.write().additional_rotation.0 = value,
+        KeyframeType::AdditionalRotationY      => stab.params.write().additional_rotation.1 = value,
+        KeyframeType::VideoRotation            => stab.params.write().video_rotation = value,
+        KeyframeType::VideoSpeed               => stab.params.write().video_speed = value,
+        KeyframeType::ZoomingCenterX           => stab.params.write().adaptive_zoom_center_offset.0 = value,
+        KeyframeType::ZoomingCenterY           => stab.params.write().adaptive_zoom_center_offset.1 = value,
+        KeyframeType::SmoothingParamSmoothness => stab.smoothing.write().current_mut().set_parameter("smoothness", value),
+        KeyframeType::LockHorizonAmount => {
+            let mut smoothing = stab.smoothing.write();
+            smoothing.horizon_lock.lock_enabled = value > 0.0;
+            smoothing.horizon_lock.horizonlockpercent = value;
+        },
+        KeyframeType::LockHorizonRoll => stab.smoothing.write().horizon_lock.horizonroll = value,
+        _ => if !crate::smoothing::set_keyframe_value(stab, typ, value) {
+            log::warn!("Not exporting the value of {typ:?}");
+        },
+    }
+}
+
+/// Project the plugin values are saved to, for the loaded project or video file.
+/// Only a project which was saved by the plugin is overwritten
+pub fn target_path(source: &str) -> String {
+    let source = Path::new(source);
+    let stem = source.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+    let is_project = source.extension().is_some_and(|x| x.eq_ignore_ascii_case("gyroflow"));
+    if is_project && stem.ends_with(SUFFIX) {
+        return source.to_string_lossy().to_string();
+    }
+    source.with_file_name(format!("{stem}{SUFFIX}.gyroflow")).to_string_lossy().to_string()
+}
+
+/// Project data with the values used for rendering. `existing` is the project being replaced,
+/// its top-level keys which the manager doesn't export (like the render settings of the app) are kept
+pub fn export(stab: &StabilizationManager, cached: &KeyframeManager, use_gyroflows_keyframes: bool, existing: Option<&serde_json::Value>) -> PluginResult<String> {
+    let data = stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None)?;
+
+    // The manager is shared with other instances, and it has to keep the project's own keyframes
+    let mut copy = StabilizationManager::default();
+    crate::lens_db::share_with(&mut copy);
+    let mut is_preset = false;
+    copy.import_gyroflow_data(data.as_bytes(), true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true)?;
+    merge_overrides(&copy, cached, use_gyroflows_keyframes);
+
+    let mut data: serde_json::Value = serde_json::from_str(&copy.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None)?)?;
+    if let (Some(data), Some(existing)) = (data.as_object_mut(), existing.and_then(|x| x.as_object())) {
+        for (key, value) in existing {
+            if !data.contains_key(key) {
+                data.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Ok(serde_json::to_string_pretty(&data)?)
+}
+
+/// Merges the values used for rendering into the manager's own state.
+/// `cached` are the values from `GyroflowPluginBaseInstance::cache_keyframes`: keyframed parameters have
+/// several keyframes, others a single value at timestamp 0
+pub fn merge_overrides(stab: &StabilizationManager, cached: &KeyframeManager, use_gyroflows_keyframes: bool) {
+    for typ in cached.get_all_keys() {
+        let Some(keys) = cached.get_keyframes(typ) else { continue; };
+        if keys.is_empty() { continue; }
+        // The same precedence as in `set_keyframe_provider`
+        if use_gyroflows_keyframes && stab.keyframes.read().is_keyframed_internally(typ) { continue; }
+
+        if keys.len() == 1 && keys.contains_key(&0) {
+            set_static_value(stab, typ, keys[&0].value);
+            stab.keyframes.write().clear_type(typ);
+        } else if *typ == KeyframeType::VideoSpeed {
+            // Internal speed keyframes are at ramped timestamps, which depend on the keyframes themselves
+            log::warn!("Editor keyframes of the video speed are not exported");
+        } else {
+            let mut keyframes = stab.keyframes.write();
+            keyframes.clear_type(typ);
+            for (ts, kf) in keys {
+                keyframes.set(typ, *ts, kf.value);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+
+// Parameter schema shared by all plugins.
+// Every host generates its parameter registration from `PARAM_DEFINITIONS`, so adding a parameter only requires adding an entry here
+// (and a variant at the end of the `Params` enum). Parameters available in After Effects or Premiere also need the next
+// `adobe_order`, which keeps the disk IDs of the parameters in saved projects.
+
+use std::sync::OnceLock;
+use crate::{ Params, KeyframeType };
+
+/// Set of plugin hosts a parameter is available in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hosts(u8);
+impl Hosts {
+    pub const AFTER_EFFECTS: Self = Self(1 << 0);
+    pub const PREMIERE:      Self = Self(1 << 1);
+    pub const OPENFX:        Self = Self(1 << 2);
+    pub const FREI0R:        Self = Self(1 << 3);
+
+    pub const ADOBE:   Self = Self::AFTER_EFFECTS.with(Self::PREMIERE);
+    pub const EDITORS: Self = Self::ADOBE.with(Self::OPENFX);
+    pub const ALL:     Self = Self::EDITORS.with(Self::FREI0R);
+
+    pub const fn with(self, other: Self) -> Self { Self(self.0 | other.0) }
+    pub const fn contains(self, other: Self) -> bool { self.0 & other.0 == other.0 }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unit {
+    None,
+    Percent,
+    Degrees,
+    Pixels,
+    Seconds,
+    Milliseconds,
+}
+
+#[derive(Copy, Clone)]
+pub enum ParameterType {
+    HiddenString,
+    TextBox,
+    Text,
+    Slider   { min: f64, max: f64, default: f64 },
+    Checkbox { default: bool },
+    Button,
+    Group    { end: Params, opened: bool, parameters: &'static [ParamDefinition] },
+    Select   { options: &'static [&'static str], default: &'static str },
+    /// Select with options only known at runtime
+    DynamicSelect { options: fn() -> Vec<String>, default: &'static str },
+}
+
+pub struct ParamDefinition {
+    pub param: Params,
+    /// Parameter name used by the hosts, equal to the `Params` variant name
+    pub id: &'static str,
+    pub label: &'static str,
+    pub hint: &'static str,
+    pub typ: ParameterType,
+    pub unit: Unit,
+    /// Whether the value can change over time and is read at the current frame
+    pub keyframable: bool,
+    /// Gyroflow keyframe driven by this parameter, and the scale of the parameter value (`keyframe value = param value / scale`)
+    pub keyframe: Option<(KeyframeType, f64)>,
+    pub hosts: Hosts,
+    /// Name used by hosts which identified this parameter differently before the schema existed (frei0r)
+    pub alias: Option<&'static str>,
+    /// Registration order of parameters added after the first release, in After Effects and Premiere.
+    /// These hosts give the parameters their disk IDs in the order they are registered, so the parameters with
+    /// an order are registered after all the others, by this number
+    pub adobe_order: Option<u16>,
+}
+
+impl ParamDefinition {
+    const DEFAULT: Self = Self {
+        param:       Params::Logo,
+        id:          "",
+        label:       "",
+        hint:        "",
+        typ:         ParameterType::Button,
+        unit:        Unit::None,
+        keyframable: false,
+        keyframe:    None,
+        hosts:       Hosts::EDITORS,
+        alias:       None,
+        adobe_order: None,
+    };
+
+    /// All definitions, including the ones nested in groups, in registration order
+    pub fn all() -> &'static [&'static ParamDefinition] {
+        static ALL: OnceLock<Vec<&'static ParamDefinition>> = OnceLock::new();
+        ALL.get_or_init(|| {
+            fn add(list: &mut Vec<&'static ParamDefinition>, defs: &'static [ParamDefinition]) {
+                for def in defs {
+                    list.push(def);
+                    if let ParameterType::Group { parameters, .. } = def.typ {
+                        add(list, parameters);
+                    }
+                }
+            }
+            let mut list = Vec::new();
+            add(&mut list, PARAM_DEFINITIONS);
+            list
+        })
+    }
+
+    pub fn find(param: Params) -> Option<&'static ParamDefinition> {
+        Self::all().iter().copied().find(|x| x.param == param)
+    }
+
+    pub fn find_by_keyframe(typ: &KeyframeType) -> Option<&'static ParamDefinition> {
+        Self::all().iter().copied().find(|x| x.keyframe.as_ref().map(|k| &k.0) == Some(typ))
+    }
+
+    pub fn is_available_in(&self, host: Hosts) -> bool {
+        self.hosts.contains(host)
+    }
+
+    /// Index of the default option of a `Select` parameter
+    pub fn default_index(&self) -> i32 {
+        match self.typ {
+            ParameterType::Select { options, default } => options.iter().position(|x| *x == default).unwrap_or(0) as i32,
+            ParameterType::DynamicSelect { options, default } => options().iter().position(|x| x == default).unwrap_or(0) as i32,
+            _ => 0
+        }
+    }
+
+    /// Options of a `Select` or `DynamicSelect` parameter
+    pub fn options(&self) -> Vec<String> {
+        match self.typ {
+            ParameterType::Select { options, .. } => options.iter().map(|x| x.to_string()).collect(),
+            ParameterType::DynamicSelect { options, .. } => options(),
+            _ => Vec::new()
+        }
+    }
+}
+
+macro_rules! param {
+    ($param:ident, $typ:expr $(, $field:ident: $value:expr)* $(,)?) => {
+        ParamDefinition { param: Params::$param, id: stringify!($param), typ: $typ, $($field: $value,)* ..ParamDefinition::DEFAULT }
+    };
+}
+use ParameterType::*;
+
+pub static PARAM_DEFINITIONS: &[ParamDefinition] = &[
+    param!(InstanceId,          HiddenString),
+    param!(ProjectPath,         HiddenString, label: "Project file or video", hosts: Hosts::ALL, alias: Some("Project")),
+    param!(ProjectData,         HiddenString),
+    param!(EmbeddedLensProfile, HiddenString),
+    param!(EmbeddedPreset,      HiddenString),
+    param!(ProjectOverrides,    HiddenString, adobe_order: Some(21)),
+    param!(ProjectGroup, Group { end: Params::ProjectGroupEnd, opened: true, parameters: &[
+        param!(Status,            Text,   label: "Status",                   hint: "Status"),
+        param!(LoadCurrent,       Button, label: "Load for current file",    hint: "Try to load project file for current video file, or try to stabilize that video file directly", hosts: Hosts::OPENFX),
+        param!(Browse,            Button, label: "Browse",                   hint: "Browse for the Gyroflow project file"),
+        param!(LoadLens,          Button, label: "Load preset/lens profile", hint: "Browse for the lens profile or a preset"),
+        param!(LoadPreset,        DynamicSelect { options: crate::presets::options, default: crate::presets::NO_PRESET }, label: "Preset", hint: "Load a preset saved with the \"Save preset\" button. Presets saved later are listed after restarting the editor, until then use \"Load preset/lens profile\".", adobe_order: Some(3)),
+        param!(SavePreset,        Button, label: "Save preset",              hint: "Save the values of the adjustment parameters as a preset, which can also be loaded in Gyroflow", adobe_order: Some(4)),
+        param!(OpenGyroflow,      Button, label: "Open Gyroflow",            hint: "Open project in Gyroflow"),
+        param!(ReloadProject,     Button, label: "Reload project",           hint: "Reload currently loaded project"),
+        param!(OpenRecentProject, Button, label: "Last saved project",       hint: "Load most recently saved project in the Gyroflow app"),
+    ] }, label: "Gyroflow project"),
+    param!(AdjustGroup, Group { end: Params::AdjustGroupEnd, opened: true, parameters: &[
+        param!(SmoothingAlgorithm,     DynamicSelect { options: crate::smoothing::names, default: "Default" }, label: "Smoothing algorithm", hint: "Smoothing algorithm. Only the parameters of the selected algorithm are enabled", adobe_order: Some(5)),
+        param!(Smoothness,             Slider { min: 1.0,    max: 300.0, default: 50.0 },  label: "Smoothness",       hint: "Smoothness",                   unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamSmoothness, 100.0)), hosts: Hosts::ALL),
+        param!(PerAxis,                Checkbox { default: false }, label: "Per axis", hint: "Use a separate smoothness for each axis", adobe_order: Some(6)),
+        param!(SmoothnessPitch,        Slider { min: 1.0,    max: 300.0, default: 50.0 },  label: "Pitch smoothness", hint: "Smoothness of the pitch axis", unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamPitch,      100.0)), adobe_order: Some(7)),
+        param!(SmoothnessYaw,          Slider { min: 1.0,    max: 300.0, default: 50.0 },  label: "Yaw smoothness",   hint: "Smoothness of the yaw axis",   unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamYaw,        100.0)), adobe_order: Some(8)),
+        param!(SmoothnessRoll,         Slider { min: 1.0,    max: 300.0, default: 50.0 },  label: "Roll smoothness",  hint: "Smoothness of the roll axis",  unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamRoll,       100.0)), adobe_order: Some(9)),
+        param!(MaxSmoothness,          Slider { min: 0.1,    max: 5.0,   default: 1.0 },   label: "Max smoothness",   hint: "Max smoothness, in seconds",   unit: Unit::Seconds, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamTimeConstant,  1.0)), adobe_order: Some(10)),
+        param!(MaxSmoothnessAtHighVelocity, Slider { min: 0.01, max: 1.0, default: 0.1 }, label: "Max smoothness at high velocity", hint: "Max smoothness when the camera moves fast, in seconds", unit: Unit::Seconds, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamTimeConstant2, 1.0)), adobe_order: Some(11)),
+        param!(TimeConstant,           Slider { min: 0.01,   max: 10.0,  default: 0.25 },  label: "Time constant",    hint: "Time constant, in seconds",    unit: Unit::Seconds, keyframable: true, keyframe: Some((KeyframeType::SmoothingParamTimeConstant,  1.0)), adobe_order: Some(12)),
+        param!(ZoomLimit,              Slider { min: 51.0,   max: 300.0, default: 130.0 }, label: "Zoom limit",       hint: "Zoom limit",                   unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::MaxZoom,                  1.0))),
+        param!(ZoomMode,               Select { options: &["Disabled", "Static", "Dynamic"], default: "Dynamic" }, label: "Zooming", hint: "Disabled shows the black borders, static zoom is constant for the whole clip, dynamic zoom follows the camera motion", adobe_order: Some(15)),
+        param!(ZoomSpeed,              Slider { min: 0.1,    max: 15.0,  default: 4.0 },   label: "Zooming speed",    hint: "Window of the dynamic zoom in seconds, lower values react faster", unit: Unit::Seconds, adobe_order: Some(16)),
+        param!(ZoomMethod,             Select { options: &["Gaussian filter", "Envelope follower"], default: "Envelope follower" }, label: "Zooming method", hint: "Method of the dynamic zoom", adobe_order: Some(17)),
+        param!(LensCorrectionStrength, Slider { min: 0.0,    max: 100.0, default: 100.0 }, label: "Lens correction",  hint: "Lens correction",              unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::LensCorrectionStrength,   100.0))),
+        param!(HorizonLockAmount,      Slider { min: 0.0,    max: 100.0, default: 0.0 },   label: "Horizon lock",     hint: "Horizon lock amount",          unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::LockHorizonAmount,        1.0))),
+        param!(HorizonLockRoll,        Slider { min: -100.0, max: 100.0, default: 0.0 },   label: "Horizon roll",     hint: "Horizon lock roll adjustment", unit: Unit::Degrees, keyframable: true, keyframe: Some((KeyframeType::LockHorizonRoll,          1.0))),
+        param!(PositionX,              Slider { min: -100.0, max: 100.0, default: 0.0 },   label: "Position offset X", hint: "Position offset X",          unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::ZoomingCenterX,           100.0)), hosts: Hosts::ALL, adobe_order: Some(13)),
+        param!(PositionY,              Slider { min: -100.0, max: 100.0, default: 0.0 },   label: "Position offset Y", hint: "Position offset Y",          unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::ZoomingCenterY,           100.0)), hosts: Hosts::ALL, adobe_order: Some(14)),
+        param!(AdditionalPitch,        Slider { min: -180.0, max: 180.0, default: 0.0 },   label: "Additional pitch", hint: "Additional pitch rotation",    unit: Unit::Degrees, keyframable: true, keyframe: Some((KeyframeType::AdditionalRotationY,      1.0))),
+        param!(AdditionalYaw,          Slider { min: -180.0, max: 180.0, default: 0.0 },   label: "Additional yaw",   hint: "Additional yaw rotation",      unit: Unit::Degrees, keyframable: true, keyframe: Some((KeyframeType::AdditionalRotationX,      1.0))),
+        param!(Rotation,               Slider { min: -360.0, max: 360.0, default: 0.0 },   label: "Video rotation",   hint: "Video rotation",               unit: Unit::Degrees, keyframable: true, keyframe: Some((KeyframeType::VideoRotation,            1.0))),
+        param!(InputRotation,          Slider { min: -360.0, max: 360.0, default: 0.0 },   label: "Input rotation",   hint: "Input rotation",               unit: Unit::Degrees, keyframable: true),
+        param!(Fov,                    Slider { min: 0.1,    max: 3.0,   default: 1.0 },   label: "FOV",              hint: "FOV",                                               keyframable: true, keyframe: Some((KeyframeType::Fov,                      1.0))),
+        param!(VideoSpeed,             Slider { min: 0.0001, max: 1000.0, default: 100.0 }, label: "Video speed",     hint: "Use this slider to change video speed or keyframe it, instead of built-in speed changes in the editor", unit: Unit::Percent, keyframable: true, keyframe: Some((KeyframeType::VideoSpeed, 100.0)), hosts: Hosts::OPENFX),
+        param!(DisableStretch,         Checkbox { default: false }, label: "Disable Gyroflow's stretch", hint: "If you used Input stretch in the lens profile in Gyroflow, and you de-stretched the video separately in your editor (by setting anamorphic squeeze factor), check this to disable Gyroflow's internal stretching."),
+        param!(FrameReadoutTime,       Slider { min: -100.0, max: 100.0, default: 0.0 },   label: "Frame readout time", hint: "Rolling shutter correction: time it takes the sensor to read one frame, in milliseconds. 0 disables the correction", unit: Unit::Milliseconds, adobe_order: Some(18)),
+        param!(ReadoutDirection,       Select { options: &["Top to bottom", "Bottom to top", "Left to right", "Right to left"], default: "Top to bottom" }, label: "Readout direction", hint: "Rolling shutter readout direction of the sensor", adobe_order: Some(19)),
+        param!(SyncOffsetMs,           Slider { min: -1000.0, max: 1000.0, default: 0.0 }, label: "Sync offset",      hint: "Added to the gyro sync offsets of the project, in milliseconds. Keyframe it to correct drift", unit: Unit::Milliseconds, keyframable: true, adobe_order: Some(20)),
+        param!(IntegrationMethod,      Select { options: &["None", "Complementary", "VQF", "Simple gyro", "Simple gyro + accel", "Mahony", "Madgwick"], default: "VQF" }, label: "Integration method", hint: "IMU integration method"),
+        //param!(FusionStartFrame,     Slider { min: 0.0, max: 100000.0, default: 0.0 }, label: "Fusion Start Frame", hint: "Fusion Start Frame (from Project Settings)", hosts: Hosts::OPENFX),
+    ] }, label: "Adjust parameters"),
+    param!(KeyframesGroup, Group { end: Params::KeyframesGroupEnd, opened: false, parameters: &[
+        param!(UseGyroflowsKeyframes,  Checkbox { default: false }, label: "Use Gyroflow's keyframes",      hint: "Use internal Gyroflow's keyframes, instead of the editor ones."),
+        param!(StabilizationSpeedRamp, Checkbox { default: true },  label: "Adjust stabilization to speed", hint: "When you speed ramp the clip, let Gyroflow adjust the stabilization amount to the video speed.", hosts: Hosts::ADOBE),
+        param!(RecalculateKeyframes,   Button, label: "Recalculate keyframes", hint: "Recalculate keyframes after adjusting the splines (in Fusion mode)"),
+        param!(CreateCamera,           Button, label: "Create camera",         hint: "Create camera layer", hosts: Hosts::AFTER_EFFECTS),
+    ] }, label: "Keyframes"),
+    param!(OutputSizeGroup, Group { end: Params::OutputSizeGroupEnd, opened: false, parameters: &[
+        param!(OutputWidth,          Slider { min: 1.0, max: 16384.0, default: 3840.0 }, label: "Width",  hint: "Width",  unit: Unit::Pixels),
+        param!(OutputHeight,         Slider { min: 1.0, max: 16384.0, default: 2160.0 }, label: "Height", hint: "Height", unit: Unit::Pixels),
+        param!(OutputSizeToTimeline, Button, label: "Fit to timeline", hint: "Set the output size to the timeline dimensions"),
+        param!(OutputSizeSwap,       Button, label: "Swap",            hint: "Swap width and height"),
+        param!(Interpolation,        Select { options: &["Lanczos4", "RobidouxSharp", "Bilinear", "Bicubic", "Robidoux", "Mitchell", "CatmullRom"], default: "Lanczos4" }, label: "Interpolation", hint: "Scaling interpolation method"),
+    ] }, label: "Output size"),
+    param!(ToggleOverview,     Checkbox { default: false }, label: "Stabilization overview",         hint: "Zooms out the view to see the stabilization results. Disable this before rendering.", hosts: Hosts::ALL, alias: Some("Overview")),
+    param!(DontDrawOutside,    Checkbox { default: false }, label: "Don't draw outside source clip", hint: "When clip and timeline aspect ratio don't match, draw the final image inside the source clip, instead of drawing outside it.", keyframable: true, hosts: Hosts::OPENFX),
+    param!(IncludeProjectData, Checkbox { default: false }, label: "Embed .gyroflow data in plugin", hint: "If you intend to share the project to someone else, the plugin can embed the Gyroflow project data including gyro data inside the video editor project. This way you don't have to share .gyroflow project files. Enabling this option will make the project bigger."),
+    param!(WatchProjectFiles,  Checkbox { default: true },  label: "Reload when project changes",    hint: "Automatically reload the project when the .gyroflow file or its lens profile is saved again, for example after making changes in the Gyroflow app.", adobe_order: Some(1)),
+    param!(ExtractProjectData, Button,                      label: "Save embedded project",          hint: "Save the Gyroflow project data embedded in the plugin to a .gyroflow file and load the project from it, so it can be opened in Gyroflow.", adobe_order: Some(2)),
+    param!(InfoGroup, Group { end: Params::InfoGroupEnd, opened: true, parameters: &[
+        param!(LoadedProject, Text, label: "Loaded project",      hint: "Loaded project or video file"),
+        param!(LoadedPreset,  Text, label: "Loaded preset",       hint: "Loaded preset"),
+        param!(LoadedLens,    Text, label: "Loaded lens profile", hint: "Loaded lens profile"),
+    ] }, label: "Info", hosts: Hosts::OPENFX),
+    param!(TimestampScale, Slider { min: 0.0, max: 100.0, default: 1.0 }, label: "Scale for the input timestamp", hint: "Scale for the input timestamp", hosts: Hosts::FREI0R),
+    param!(TrimStart,      Slider { min: 0.0, max: 100000.0, default: 0.0 }, label: "Trim start", hint: "Start of the used part of the source media, in seconds", unit: Unit::Seconds, hosts: Hosts::FREI0R),
+    param!(TrimEnd,        Slider { min: 0.0, max: 100000.0, default: 0.0 }, label: "Trim end",   hint: "End of the used part of the source media, in seconds. 0 uses the media until its end", unit: Unit::Seconds, hosts: Hosts::FREI0R),
+];
@@ -0,0 +1,117 @@
+// Image sequences (DPX, EXR, TIFF...) from cinema cameras and transcodes.
+// Hosts refer to them in different ways: printf patterns (`A001_%05d.dpx`), hashes (`A001_#####.dpx`),
+// the frame range in brackets (`A001_[00001-00240].dpx`) or just the path of one of the frames.
+// Some hosts also number the frames of a sequence like its files, so the first frame is at timestamp 0.
+
+use std::collections::HashMap;
+use std::path::{ Path, PathBuf };
+use std::sync::OnceLock;
+use parking_lot::Mutex;
+use regex::Regex;
+
+/// Extensions of image files which are treated as a frame of a sequence when the name ends with a number
+pub const IMAGE_EXTENSIONS: &[&str] = &["dpx", "exr", "tif", "tiff", "png", "jpg", "jpeg", "dng", "cin", "tga", "bmp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSequence {
+    pub dir: PathBuf,
+    /// File name before the frame number
+    pub prefix: String,
+    /// File name after the frame number, including the extension
+    pub suffix: String,
+    /// Number of digits of the frame number, 0 if it's not padded
+    pub padding: usize,
+    /// Frame range from the path, if it was specified
+    pub range: Option<(i64, i64)>,
+}
+
+fn regexes() -> &'static [Regex; 4] {
+    static RE: OnceLock<[Regex; 4]> = OnceLock::new();
+    RE.get_or_init(|| [
+        Regex::new(r"^(.*?)%(\d*)d(.*)$").unwrap(),
+        Regex::new(r"^(.*?)(#+)(.*)$").unwrap(),
+        Regex::new(r"^(.*?)\[(\d+)-(\d+)\](.*)$").unwrap(),
+        Regex::new(r"^(.*?)(\d+)(\.[A-Za-z0-9]+)$").unwrap(),
+    ])
+}
+
+impl ImageSequence {
+    /// Returns `None` if the path isn't an image sequence
+    pub fn parse(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name = path.file_name()?.to_string_lossy().to_string();
+        let [printf, hashes, brackets, frame] = regexes();
+
+        let (prefix, suffix, padding, range) = if let Some(c) = printf.captures(&name) {
+            (c[1].to_owned(), c[3].to_owned(), c[2].parse().unwrap_or(0), None)
+        } else if let Some(c) = hashes.captures(&name) {
+            (c[1].to_owned(), c[3].to_owned(), c[2].len(), None)
+        } else if let Some(c) = brackets.captures(&name) {
+            (c[1].to_owned(), c[4].to_owned(), c[2].len(), Some((c[2].parse().ok()?, c[3].parse().ok()?)))
+        } else if let Some(c) = frame.captures(&name) {
+            let ext = c[3].trim_start_matches('.').to_ascii_lowercase();
+            if !IMAGE_EXTENSIONS.contains(&ext.as_str()) { return None; }
+            (c[1].to_owned(), c[3].to_owned(), c[2].len(), None)
+        } else {
+            return None;
+        };
+        Some(Self { dir, prefix, suffix, padding, range })
+    }
+
+    pub fn frame_path(&self, frame: i64) -> String {
+        self.dir.join(format!("{}{:0pad$}{}", self.prefix, frame, self.suffix, pad = self.padding)).to_string_lossy().to_string()
+    }
+
+    /// Frame numbers from the path, or of the files on disk
+    pub fn frame_range(&self) -> Option<(i64, i64)> {
+        self.range.or_else(|| {
+            std::fs::read_dir(&self.dir).ok()?.flatten().filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let number = name.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+                if number.is_empty() || !number.bytes().all(|x| x.is_ascii_digit()) { return None; }
+                number.parse::<i64>().ok()
+            }).fold(None, |r, x| Some(r.map_or((x, x), |(a, b): (i64, i64)| (a.min(x), b.max(x)))))
+        })
+    }
+    pub fn first_frame(&self) -> Option<i64> {
+        self.frame_range().map(|x| x.0)
+    }
+
+    /// Name of the clip without the frame number, used to find the project
+    pub fn base_name(&self) -> String {
+        let name = self.prefix.trim_end_matches(['_', '.', '-', ' ']);
+        if name.is_empty() {
+            self.dir.file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_default()
+        } else {
+            name.to_owned()
+        }
+    }
+
+    /// Whether both paths refer to the same sequence, regardless of the notation and the folder
+    pub fn matches(&self, other: &Self) -> bool {
+        self.prefix.eq_ignore_ascii_case(&other.prefix) && self.suffix.eq_ignore_ascii_case(&other.suffix)
+    }
+}
+
+/// First frame number of the sequence the video url refers to, `None` for video files.
+/// Cached, because it's needed for every rendered frame and the frames on disk may have to be listed
+pub fn first_frame_of(url: &str) -> Option<i64> {
+    static FIRST_FRAMES: OnceLock<Mutex<HashMap<String, Option<i64>>>> = OnceLock::new();
+    let mut cache = FIRST_FRAMES.get_or_init(Default::default).lock();
+    *cache.entry(url.to_owned()).or_insert_with(|| {
+        ImageSequence::parse(&gyroflow_core::filesystem::url_to_path(url))?.first_frame()
+    })
+}
+
+/// Path of a file which can be opened: the first frame for sequence notations, the unchanged path otherwise
+pub fn media_path(path: &str) -> String {
+    if let Some(seq) = ImageSequence::parse(path) {
+        if !Path::new(path).exists() {
+            if let Some(first) = seq.first_frame() {
+                return seq.frame_path(first);
+            }
+        }
+    }
+    path.to_owned()
+}
@@ -0,0 +1,115 @@
+// Discovery of the .gyroflow project files saved next to a video file.
+// Every candidate is ranked, so the best match is loaded automatically and hosts can show the other ones.
+
+use std::path::{ Path, PathBuf };
+use std::time::SystemTime;
+use crate::sequence::ImageSequence;
+
+/// Environment variable with the folders to search, separated by `;`, relative to the folder of the video
+pub const SUBFOLDERS_ENV_VAR: &str = "GYROFLOW_PLUGIN_PROJECT_FOLDERS";
+/// Gyroflow setting with the folders to search, as an array or a `;` separated string
+pub const SUBFOLDERS_SETTING: &str = "pluginProjectFolders";
+pub const DEFAULT_SUBFOLDERS: &[&str] = &[".", "gyroflow", "../projects"];
+
+/// Suffixes added to the video name by Gyroflow and common workflows
+pub const KNOWN_SUFFIXES: &[&str] = &["_stabilized", "_stab", "_gyroflow"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameMatch {
+    /// `<video name>.gyroflow`
+    Exact,
+    /// `<video name><known suffix>.gyroflow`
+    KnownSuffix,
+    /// Any other name beginning with the video name
+    Prefix,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectCandidate {
+    pub path: PathBuf,
+    pub name_match: NameMatch,
+    pub modified: Option<SystemTime>,
+    /// Whether the `videofile` in the project points to the video, `None` if the project doesn't have it
+    pub videofile_matches: Option<bool>,
+    /// Index of the folder in `subfolders()`
+    pub folder: usize,
+}
+
+pub fn subfolders() -> Vec<String> {
+    let split = |x: &str| x.split(';').map(str::trim).filter(|x| !x.is_empty()).map(str::to_owned).collect::<Vec<_>>();
+    if let Ok(v) = std::env::var(SUBFOLDERS_ENV_VAR) {
+        return split(&v);
+    }
+    match gyroflow_core::settings::try_get(SUBFOLDERS_SETTING) {
+        Some(serde_json::Value::Array(v))  => v.iter().filter_map(|x| x.as_str().map(str::to_owned)).collect(),
+        Some(serde_json::Value::String(v)) => split(&v),
+        _ => DEFAULT_SUBFOLDERS.iter().map(|x| x.to_string()).collect()
+    }
+}
+
+fn videofile_name(project: &Path) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct ProjectHeader { videofile: Option<String> }
+
+    let file = std::fs::File::open(project).ok()?;
+    let header: ProjectHeader = serde_json::from_reader(std::io::BufReader::new(file)).ok()?;
+    let videofile = header.videofile.filter(|x| !x.is_empty())?;
+    // The project may come from another machine, so only the file name is compared
+    if videofile.contains("://") {
+        Some(gyroflow_core::filesystem::get_filename(&videofile))
+    } else {
+        videofile.rsplit(['/', '\\']).next().map(str::to_owned)
+    }
+}
+
+fn name_match(file_name: &str, video_name: &str) -> Option<NameMatch> {
+    let rest = file_name.strip_suffix(".gyroflow")?.strip_prefix(video_name)?;
+    Some(if rest.is_empty() {
+        NameMatch::Exact
+    } else if KNOWN_SUFFIXES.iter().any(|x| rest.eq_ignore_ascii_case(x)) {
+        NameMatch::KnownSuffix
+    } else {
+        NameMatch::Prefix
+    })
+}
+
+/// Projects for the video in `file_path` from the folders in `subfolders`, best match first.
+/// Projects which were saved for a different video are skipped. Image sequences can be given in any notation
+pub fn find_projects_in(file_path: &str, subfolders: &[String]) -> Vec<ProjectCandidate> {
+    let video = Path::new(file_path);
+    let (Some(dir), Some(video_name), Some(video_file_name)) = (video.parent(), video.file_stem(), video.file_name()) else { return Vec::new(); };
+    let sequence = ImageSequence::parse(file_path);
+    let video_name = sequence.as_ref().map(|x| x.base_name()).unwrap_or_else(|| video_name.to_string_lossy().to_string());
+    let video_file_name = video_file_name.to_string_lossy();
+    let same_video = |videofile: &str| match &sequence {
+        Some(seq) => ImageSequence::parse(videofile).is_some_and(|x| x.matches(seq)),
+        None      => videofile.eq_ignore_ascii_case(&video_file_name)
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let mut candidates = Vec::new();
+    for (folder, subfolder) in subfolders.iter().enumerate() {
+        let folder_path = if subfolder == "." { dir.to_path_buf() } else { dir.join(subfolder) };
+        let Ok(entries) = std::fs::read_dir(folder_path) else { continue; };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name_match) = name_match(&file_name, &video_name) else { continue; };
+            let path = entry.path();
+            if !seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())) { continue; }
+
+            let videofile_matches = videofile_name(&path).map(|x| same_video(&x));
+            if videofile_matches == Some(false) {
+                log::debug!("Skipping {path:?}, it was saved for a different video");
+                continue;
+            }
+            let modified = entry.metadata().and_then(|x| x.modified()).ok();
+            candidates.push(ProjectCandidate { path, name_match, modified, videofile_matches, folder });
+        }
+    }
+    candidates.sort_by_key(|x| (x.name_match, x.videofile_matches != Some(true), std::cmp::Reverse(x.modified), x.folder));
+    candidates
+}
+
+pub fn find_projects(file_path: &str) -> Vec<ProjectCandidate> {
+    find_projects_in(file_path, &subfolders())
+}
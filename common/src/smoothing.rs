@@ -0,0 +1,180 @@
+// Smoothing algorithm selection and the parameters of the algorithms.
+// The algorithms and their parameter names come from gyroflow-core. Each parameter of an algorithm is driven by one of the
+// plugin parameters in `PARAMS`, which are only enabled and applied when the selected algorithm has that parameter.
+
+use std::sync::OnceLock;
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::smoothing::Smoothing;
+use crate::{ GyroflowPluginParams, KeyframeType, ParamDefinition, ParameterType, Params, PluginResult };
+
+/// Plugin parameter and the name of the algorithm parameter it drives
+pub const PARAMS: &[(Params, &str)] = &[
+    (Params::Smoothness,                  "smoothness"),
+    (Params::SmoothnessPitch,             "smoothness_pitch"),
+    (Params::SmoothnessYaw,               "smoothness_yaw"),
+    (Params::SmoothnessRoll,              "smoothness_roll"),
+    (Params::PerAxis,                     "per_axis"),
+    (Params::MaxSmoothness,               "max_smoothness"),
+    (Params::MaxSmoothnessAtHighVelocity, "alpha_0_1s"),
+    (Params::TimeConstant,                "time_constant"),
+];
+
+pub struct Algorithm {
+    pub name: String,
+    /// Names of the parameters, in the order of the core
+    pub parameters: Vec<String>,
+}
+
+pub fn algorithms() -> &'static [Algorithm] {
+    static ALGORITHMS: OnceLock<Vec<Algorithm>> = OnceLock::new();
+    ALGORITHMS.get_or_init(|| {
+        let mut smoothing = Smoothing::default();
+        smoothing.get_names().into_iter().enumerate().map(|(i, name)| {
+            smoothing.set_current(i);
+            let parameters = smoothing.current().get_parameters_json().as_array()
+                .map(|x| x.iter().filter_map(|x| x["name"].as_str().map(str::to_owned)).collect::<Vec<_>>())
+                .unwrap_or_default();
+            for x in parameters.iter().filter(|x| !PARAMS.iter().any(|p| p.1 == x.as_str())) {
+                log::warn!("Parameter {x} of the smoothing algorithm {name} is not available in the plugin");
+            }
+            Algorithm { name, parameters }
+        }).collect()
+    })
+}
+
+/// Options of `SmoothingAlgorithm`
+pub fn names() -> Vec<String> {
+    algorithms().iter().map(|x| x.name.clone()).collect()
+}
+
+pub fn selected(params: &dyn GyroflowPluginParams) -> Option<&'static Algorithm> {
+    let index = params.get_i32(Params::SmoothingAlgorithm).ok()?;
+    algorithms().get(usize::try_from(index).ok()?)
+}
+
+fn core_name(param: Params) -> Option<&'static str> {
+    PARAMS.iter().find(|x| x.0 == param).map(|x| x.1)
+}
+fn scale(param: Params) -> f64 {
+    ParamDefinition::find(param).and_then(|x| x.keyframe.as_ref()).map(|x| x.1).unwrap_or(1.0)
+}
+fn is_checkbox(param: Params) -> bool {
+    ParamDefinition::find(param).is_some_and(|x| matches!(x.typ, ParameterType::Checkbox { .. }))
+}
+
+/// Whether the parameter is used by the selected algorithm. Other parameters are always active
+pub fn is_active(params: &dyn GyroflowPluginParams, param: Params) -> bool {
+    let Some(name) = core_name(param) else { return true; };
+    selected(params).is_some_and(|x| x.parameters.iter().any(|x| x == name))
+}
+
+pub fn update_enabled(params: &mut dyn GyroflowPluginParams, loaded: bool) {
+    let _ = params.set_enabled(Params::SmoothingAlgorithm, loaded);
+    for (param, _) in PARAMS {
+        let active = is_active(params, *param);
+        let _ = params.set_enabled(*param, loaded && active);
+    }
+}
+
+/// Plugin parameter of the keyframe, for the selected algorithm
+pub fn param_for_keyframe(params: &dyn GyroflowPluginParams, typ: &KeyframeType) -> Option<&'static ParamDefinition> {
+    ParamDefinition::all().iter().copied()
+        .find(|x| x.keyframe.as_ref().map(|k| &k.0) == Some(typ) && is_active(params, x.param))
+}
+
+/// Selects the algorithm in the manager and sets the values which aren't passed as keyframes.
+/// The algorithm of the project is kept until `SmoothingAlgorithm` was read from it or set by the user
+pub fn apply(params: &dyn GyroflowPluginParams, stab: &StabilizationManager) {
+    if !crate::overrides::is_set(params, Params::SmoothingAlgorithm) { return; }
+    let Some(algorithm) = selected(params) else { return; };
+    let mut smoothing = stab.smoothing.write();
+    if smoothing.current().get_name() != algorithm.name {
+        if let Some(index) = algorithms().iter().position(|x| x.name == algorithm.name) {
+            log::info!("Smoothing algorithm: {}", algorithm.name);
+            smoothing.set_current(index);
+        }
+    }
+    for (param, name) in PARAMS {
+        let def = ParamDefinition::find(*param);
+        if def.is_none_or(|x| x.keyframe.is_some()) || !is_active(params, *param) { continue; }
+        let value = if is_checkbox(*param) {
+            params.get_bool(*param).map(|x| if x { 1.0 } else { 0.0 })
+        } else {
+            params.get_f64(*param).map(|x| x / scale(*param))
+        };
+        if let Ok(value) = value {
+            smoothing.current_mut().set_parameter(name, value);
+        }
+    }
+}
+
+/// Sets the parameters from the algorithm and its values in the manager
+pub fn read(params: &mut dyn GyroflowPluginParams, stab: &StabilizationManager) -> PluginResult<()> {
+    let smoothing = stab.smoothing.read();
+    let current = smoothing.current();
+    let name = current.get_name();
+    if let Some(index) = algorithms().iter().position(|x| x.name == name) {
+        params.set_i32(Params::SmoothingAlgorithm, index as i32)?;
+    }
+    for (param, name) in PARAMS {
+        if !is_active(params, *param) { continue; }
+        let value = current.get_parameter(name);
+        if is_checkbox(*param) {
+            params.set_bool(*param, value > 0.0)?;
+        } else {
+            params.set_f64(*param, value * scale(*param))?;
+        }
+    }
+    Ok(())
+}
+
+/// Algorithm name and its parameters as stored in the Gyroflow project, from the plugin parameters
+pub fn to_project(params: &dyn GyroflowPluginParams) -> PluginResult<(String, serde_json::Value)> {
+    let Some(algorithm) = selected(params) else { return Ok(("Default".into(), serde_json::json!([]))); };
+    let mut values = Vec::new();
+    for (param, name) in PARAMS {
+        if !is_active(params, *param) { continue; }
+        let value = if is_checkbox(*param) {
+            if params.get_bool(*param)? { 1.0 } else { 0.0 }
+        } else {
+            params.get_f64(*param)? / scale(*param)
+        };
+        values.push(serde_json::json!({ "name": name, "value": value }));
+    }
+    Ok((algorithm.name.clone(), serde_json::Value::Array(values)))
+}
+
+/// Sets the parameters from the algorithm and its parameters in a Gyroflow project or preset, returns the changed parameters
+pub fn from_project(params: &mut dyn GyroflowPluginParams, method: Option<&str>, values: Option<&serde_json::Value>) -> PluginResult<Vec<Params>> {
+    let mut changed = Vec::new();
+    if let Some(index) = method.and_then(|x| algorithms().iter().position(|a| a.name.eq_ignore_ascii_case(x))) {
+        params.set_i32(Params::SmoothingAlgorithm, index as i32)?;
+        changed.push(Params::SmoothingAlgorithm);
+    }
+    for x in values.and_then(|x| x.as_array()).into_iter().flatten() {
+        let (Some(name), Some(value)) = (x["name"].as_str(), x["value"].as_f64()) else { continue; };
+        let Some((param, _)) = PARAMS.iter().find(|x| x.1 == name) else { continue; };
+        if is_checkbox(*param) {
+            params.set_bool(*param, value > 0.0)?;
+        } else {
+            params.set_f64(*param, value * scale(*param))?;
+        }
+        changed.push(*param);
+    }
+    Ok(changed)
+}
+
+/// Sets a static value of a smoothing keyframe in the selected algorithm of the manager
+pub fn set_keyframe_value(stab: &StabilizationManager, typ: &KeyframeType, value: f64) -> bool {
+    let mut smoothing = stab.smoothing.write();
+    let name = smoothing.current().get_name();
+    let Some(algorithm) = algorithms().iter().find(|x| x.name == name) else { return false; };
+    let def = PARAMS.iter().find(|(param, core_name)| {
+        algorithm.parameters.iter().any(|x| x == core_name) &&
+            ParamDefinition::find(*param).and_then(|x| x.keyframe.as_ref()).is_some_and(|x| &x.0 == typ)
+    });
+    match def {
+        Some((_, core_name)) => { smoothing.current_mut().set_parameter(core_name, value); true },
+        None => false
+    }
+}
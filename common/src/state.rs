@@ -0,0 +1,91 @@
+// Versioned binary format of the plugin state saved inside host projects.
+// Every layout that was ever saved is kept as a frozen type, together with the step that upgrades it to the next one,
+// so the state saved by an older release is upgraded instead of being silently reset to defaults.
+// When adding or removing a serialized field: copy the current layout to a new frozen type, bump `VERSION`
+// and add the upgrade step from the previous version.
+
+use serde::{ Serialize, Deserialize, de::DeserializeOwned };
+use crate::{ GyroflowPluginBaseInstance, PluginResult };
+
+/// Prefix of the versioned state. Data without it was saved before the state was versioned.
+pub const STATE_MAGIC: &[u8; 4] = b"GFst";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionedBlob {
+    pub version: u16,
+    pub data: Vec<u8>,
+}
+
+pub trait VersionedState: Sized + Serialize {
+    /// Layout version written by this release
+    const VERSION: u16;
+
+    /// Decodes `data` saved with layout `version` and upgrades it to the current layout
+    fn upgrade(version: u16, data: &[u8]) -> PluginResult<Self>;
+
+    fn to_blob(&self) -> PluginResult<VersionedBlob> {
+        Ok(VersionedBlob { version: Self::VERSION, data: encode(self)? })
+    }
+    fn from_blob(blob: &VersionedBlob) -> PluginResult<Self> {
+        if blob.version > Self::VERSION {
+            return Err(format!("State was saved by a newer plugin version (layout {} > {})", blob.version, Self::VERSION).into());
+        }
+        Self::upgrade(blob.version, &blob.data)
+    }
+}
+
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> PluginResult<Vec<u8>> {
+    Ok(bincode::serde::encode_to_vec(value, bincode::config::legacy())?)
+}
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> PluginResult<T> {
+    Ok(bincode::serde::decode_from_slice::<T, _>(bytes, bincode::config::legacy())?.0)
+}
+
+/// `GyroflowPluginBaseInstance` as saved by every release before the state was versioned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GyroflowPluginBaseInstanceV1 {
+    pub reload_values_from_project: bool,
+    pub original_video_size: (usize, usize),
+    pub original_output_size: (usize, usize),
+    pub timeline_size: (usize, usize),
+    pub num_frames: usize,
+    pub fps: f64,
+    pub has_motion: bool,
+    pub ever_changed: bool,
+    pub cache_keyframes_every_frame: bool,
+    pub framebuffer_inverted: bool,
+    pub anamorphic_adjust_size: bool,
+    pub always_set_input_rotation: bool,
+    pub opencl_disabled: bool,
+}
+impl From<GyroflowPluginBaseInstanceV1> for GyroflowPluginBaseInstance {
+    fn from(v: GyroflowPluginBaseInstanceV1) -> Self {
+        Self {
+            reload_values_from_project:     v.reload_values_from_project,
+            original_video_size:            v.original_video_size,
+            original_output_size:           v.original_output_size,
+            timeline_size:                  v.timeline_size,
+            num_frames:                     v.num_frames,
+            fps:                            v.fps,
+            has_motion:                     v.has_motion,
+            ever_changed:                   v.ever_changed,
+            cache_keyframes_every_frame:    v.cache_keyframes_every_frame,
+            framebuffer_inverted:           v.framebuffer_inverted,
+            anamorphic_adjust_size:         v.anamorphic_adjust_size,
+            always_set_input_rotation:      v.always_set_input_rotation,
+            opencl_disabled:                v.opencl_disabled,
+            ..Default::default()
+        }
+    }
+}
+
+impl VersionedState for GyroflowPluginBaseInstance {
+    const VERSION: u16 = 1;
+
+    fn upgrade(version: u16, data: &[u8]) -> PluginResult<Self> {
+        match version {
+            1 => Ok(decode::<GyroflowPluginBaseInstanceV1>(data)?.into()),
+            _ => Err(format!("Unknown layout version of GyroflowPluginBaseInstance: {version}").into())
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Fine-tuning of the gyro to video sync from the plugin, applied on top of the sync points of the project.
+// The adjustment is keyframable to correct drift: it's interpolated linearly between its keyframes, and added to the
+// project's offsets at every sync point and at every keyframe. The project's offsets of each manager are kept here,
+// so the adjustment can be changed or removed without reloading the project.
+
+use std::collections::BTreeMap;
+use std::sync::{ Arc, OnceLock, Weak };
+use parking_lot::Mutex;
+use gyroflow_core::StabilizationManager;
+
+/// Offsets in milliseconds at timestamps in microseconds, as stored in the gyro source
+pub type Offsets = BTreeMap<i64, f64>;
+
+struct Entry {
+    stab: Weak<StabilizationManager>,
+    project_offsets: Offsets,
+    adjustment: Offsets,
+}
+fn entries() -> &'static Mutex<Vec<Entry>> {
+    static ENTRIES: OnceLock<Mutex<Vec<Entry>>> = OnceLock::new();
+    ENTRIES.get_or_init(Default::default)
+}
+
+/// Linear interpolation between the points, the first and last values are held outside of them
+pub fn value_at(points: &Offsets, timestamp_us: i64) -> Option<f64> {
+    let before = points.range(..=timestamp_us).next_back();
+    let after = points.range(timestamp_us..).next();
+    match (before, after) {
+        (Some((t0, v0)), Some((t1, v1))) if t1 > t0 => Some(v0 + (v1 - v0) * ((timestamp_us - t0) as f64 / (t1 - t0) as f64)),
+        (Some((_, v)), _) | (None, Some((_, v))) => Some(*v),
+        (None, None) => None
+    }
+}
+
+/// Project offsets with the adjustment added
+pub fn combine(project_offsets: &Offsets, adjustment: &Offsets) -> Offsets {
+    if adjustment.values().all(|x| *x == 0.0) {
+        return project_offsets.clone();
+    }
+    if project_offsets.is_empty() && adjustment.len() == 1 {
+        return adjustment.clone();
+    }
+    let mut timestamps = project_offsets.keys().copied().collect::<Vec<_>>();
+    if adjustment.len() > 1 {
+        timestamps.extend(adjustment.keys().copied());
+    }
+    timestamps.into_iter().map(|ts| {
+        (ts, value_at(project_offsets, ts).unwrap_or_default() + value_at(adjustment, ts).unwrap_or_default())
+    }).collect()
+}
+
+fn write(stab: &StabilizationManager, offsets: &Offsets) {
+    let mut gyro = stab.gyro.write();
+    gyro.clear_offsets();
+    for (ts, offset) in offsets {
+        gyro.set_offset(*ts, *offset);
+    }
+}
+
+/// Applies the adjustment to a new manager. Its current offsets are the project's offsets
+pub fn apply_new(stab: &StabilizationManager, adjustment: &Offsets) -> Offsets {
+    let project_offsets = stab.gyro.read().get_offsets().clone();
+    if !adjustment.is_empty() {
+        write(stab, &combine(&project_offsets, adjustment));
+    }
+    project_offsets
+}
+
+/// Keeps the project's offsets of the manager created with `apply_new`
+pub fn register(stab: &Arc<StabilizationManager>, project_offsets: Offsets, adjustment: Offsets) {
+    let mut entries = entries().lock();
+    entries.retain(|x| x.stab.strong_count() > 0 && !std::ptr::eq(x.stab.as_ptr(), Arc::as_ptr(stab)));
+    entries.push(Entry { stab: Arc::downgrade(stab), project_offsets, adjustment });
+}
+
+fn update(stab: &Arc<StabilizationManager>, f: impl FnOnce(&mut Entry)) {
+    let mut entries = entries().lock();
+    let index = match entries.iter().position(|x| std::ptr::eq(x.stab.as_ptr(), Arc::as_ptr(stab))) {
+        Some(index) => index,
+        None => {
+            let project_offsets = stab.gyro.read().get_offsets().clone();
+            entries.push(Entry { stab: Arc::downgrade(stab), project_offsets, adjustment: Offsets::new() });
+            entries.len() - 1
+        }
+    };
+    let entry = &mut entries[index];
+    f(entry);
+    write(stab, &combine(&entry.project_offsets, &entry.adjustment));
+}
+
+/// Changes the adjustment. The caller invalidates the smoothing and zooming
+pub fn set_adjustment(stab: &Arc<StabilizationManager>, adjustment: Offsets) {
+    update(stab, |x| x.adjustment = adjustment);
+}
+
+/// Replaces the project's offsets, keeping the adjustment
+pub fn set_project_offsets(stab: &Arc<StabilizationManager>, offsets: Offsets) {
+    update(stab, |x| x.project_offsets = offsets);
+}
+
+/// Makes the adjusted offsets the project's offsets, after they were saved to the project
+pub fn bake(stab: &Arc<StabilizationManager>) {
+    update(stab, |x| {
+        x.project_offsets = combine(&x.project_offsets, &x.adjustment);
+        x.adjustment.clear();
+    });
+}
@@ -0,0 +1,47 @@
+// Used range of the source media. Every host passes the part of the clip which is used in the timeline,
+// so the smoothing and the adaptive zoom are computed only for it, instead of for the motion in the whole recording.
+
+use gyroflow_core::StabilizationManager;
+
+/// Used range in microseconds, as a fraction of the media duration, as stored in the manager's trim ranges
+pub fn normalize(range_us: (i64, i64), duration_ms: f64) -> Option<(f64, f64)> {
+    if duration_ms <= 0.0 { return None; }
+    let start = (range_us.0 as f64 / 1000.0 / duration_ms).clamp(0.0, 1.0);
+    let end   = (range_us.1 as f64 / 1000.0 / duration_ms).clamp(0.0, 1.0);
+    (end > start).then_some((start, end))
+}
+
+/// Whether the range in microseconds is only a part of the media. Compared in milliseconds, like `set_range`
+pub fn is_sub_range(range_us: (i64, i64), duration_ms: f64) -> bool {
+    let Some((start, end)) = normalize(range_us, duration_ms) else { return false; };
+    (start * duration_ms).round() as i64 > 0 || ((end * duration_ms).round() as i64) < duration_ms.round() as i64
+}
+
+/// Current used range in microseconds, `None` when the whole media is used
+pub fn range_us(stab: &StabilizationManager) -> Option<(i64, i64)> {
+    let duration_ms = stab.params.read().duration_ms;
+    let (start, end) = stab.trim_ranges().first().cloned()?;
+    let range = ((start * duration_ms * 1000.0).round() as i64, (end * duration_ms * 1000.0).round() as i64);
+    (range != (0, (duration_ms * 1000.0).round() as i64)).then_some(range)
+}
+
+/// Sets the used range of the source media in microseconds, `None` or an empty range uses the whole media.
+/// Returns whether the range changed, the smoothing and zooming are invalidated then
+pub fn set_range(stab: &StabilizationManager, range_us: Option<(i64, i64)>) -> bool {
+    let duration_ms = stab.params.read().duration_ms;
+    if duration_ms <= 0.0 { return false; }
+
+    let new_range = range_us.and_then(|x| normalize(x, duration_ms)).unwrap_or((0.0, 1.0));
+    let old_range = stab.trim_ranges().first().cloned().unwrap_or((0.0, 1.0));
+    // Compared in milliseconds, the hosts' frame times don't round trip exactly
+    let to_ms = |x: (f64, f64)| ((x.0 * duration_ms).round() as i64, (x.1 * duration_ms).round() as i64);
+    if to_ms(old_range) == to_ms(new_range) {
+        return false;
+    }
+    log::info!("Trim range changed: {:?} != {:?}", to_ms(old_range), to_ms(new_range));
+
+    stab.set_trim_ranges(vec![new_range]);
+    stab.invalidate_blocking_smoothing();
+    stab.invalidate_blocking_zooming();
+    true
+}
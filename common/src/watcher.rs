@@ -0,0 +1,105 @@
+// Watches loaded projects and the lens profiles they reference for changes on disk.
+// Every change bumps the generation of the projects using the file, and instances which loaded an older generation
+// reload the project on the next render, just like after pressing "Reload project".
+// Directories can also be watched with a callback, which is used to reload the lens profile database.
+
+use std::collections::{ HashMap, HashSet };
+use std::path::{ Path, PathBuf };
+use std::sync::OnceLock;
+use std::time::Duration;
+use parking_lot::Mutex;
+use notify_debouncer_mini::{ new_debouncer, Debouncer, DebounceEventResult, notify::{ RecommendedWatcher, RecursiveMode } };
+
+// Gyroflow app and text editors usually save files in a few steps, report them as one change
+const DEBOUNCE_TIME: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct WatcherState {
+    /// Watched file -> project paths which use it
+    files: HashMap<PathBuf, HashSet<String>>,
+    /// Project path -> number of changes since the project was first watched
+    generations: HashMap<String, u64>,
+    watched_dirs: HashSet<PathBuf>,
+    /// Recursively watched directory -> called when anything inside changes
+    directories: Vec<(PathBuf, fn())>,
+}
+
+fn state() -> &'static Mutex<WatcherState> {
+    static STATE: OnceLock<Mutex<WatcherState>> = OnceLock::new();
+    STATE.get_or_init(Default::default)
+}
+
+fn debouncer() -> Option<&'static Mutex<Debouncer<RecommendedWatcher>>> {
+    static DEBOUNCER: OnceLock<Option<Mutex<Debouncer<RecommendedWatcher>>>> = OnceLock::new();
+    DEBOUNCER.get_or_init(|| {
+        match new_debouncer(DEBOUNCE_TIME, on_events) {
+            Ok(d) => Some(Mutex::new(d)),
+            Err(e) => { log::error!("Failed to create the file watcher: {e:?}"); None }
+        }
+    }).as_ref()
+}
+
+fn on_events(result: DebounceEventResult) {
+    match result {
+        Ok(events) => {
+            let callbacks = {
+                let mut state = state().lock();
+                let changed = events.iter()
+                    .filter_map(|e| state.files.get(&e.path))
+                    .flatten()
+                    .cloned()
+                    .collect::<HashSet<_>>();
+                for project in changed {
+                    log::info!("Project files changed: {project}");
+                    *state.generations.entry(project).or_default() += 1;
+                }
+                state.directories.iter()
+                    .filter(|(dir, _)| events.iter().any(|e| e.path.starts_with(dir)))
+                    .map(|(_, cb)| *cb)
+                    .collect::<Vec<_>>()
+            };
+            // Called without the lock, so the callbacks can take their time
+            for cb in callbacks {
+                cb();
+            }
+        },
+        Err(e) => log::error!("File watcher error: {e:?}")
+    }
+}
+
+/// Starts watching `project_path` and the additional `files` it uses
+pub fn watch(project_path: &str, files: &[&str]) {
+    let Some(debouncer) = debouncer() else { return; };
+    let mut state = state().lock();
+    for file in std::iter::once(project_path).chain(files.iter().copied()).filter(|x| !x.is_empty()) {
+        // Watch the parent directory, because files are often saved by replacing them, which ends the watch of the file itself
+        let file = Path::new(file);
+        let (Some(dir), Some(name)) = (file.parent(), file.file_name()) else { continue; };
+        let Ok(dir) = dir.canonicalize() else { continue; };
+        if !state.watched_dirs.contains(&dir) {
+            if let Err(e) = debouncer.lock().watcher().watch(&dir, RecursiveMode::NonRecursive) {
+                log::error!("Failed to watch {dir:?}: {e:?}");
+                continue;
+            }
+            state.watched_dirs.insert(dir.clone());
+        }
+        state.files.entry(dir.join(name)).or_default().insert(project_path.to_owned());
+    }
+    state.generations.entry(project_path.to_owned()).or_default();
+}
+
+/// Calls `on_change` after anything in `dir` or its subdirectories changes
+pub fn watch_directory(dir: &Path, on_change: fn()) {
+    let Some(debouncer) = debouncer() else { return; };
+    let Ok(dir) = dir.canonicalize() else { return; };
+    if let Err(e) = debouncer.lock().watcher().watch(&dir, RecursiveMode::Recursive) {
+        log::error!("Failed to watch {dir:?}: {e:?}");
+        return;
+    }
+    state().lock().directories.push((dir, on_change));
+}
+
+/// Number of changes of the project files since it was first watched
+pub fn generation(project_path: &str) -> u64 {
+    state().lock().generations.get(project_path).copied().unwrap_or_default()
+}
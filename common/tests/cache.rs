@@ -0,0 +1,43 @@
+// Manager caches limited by the estimated memory usage.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn manager_cache_is_limited_by_budget() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, instance, result) = load(&mut params, &path);
+    let stab = result.unwrap();
+
+    let size = gyroflow_plugin_base::cache::estimated_size(&stab);
+    assert!(size > 0);
+    assert_eq!(base.manager_cache.lock().used_bytes(), size);
+    // Instances only get a share of the budget, they keep the managers alive after the global cache evicted them
+    assert_eq!(instance.managers.budget_bytes(), gyroflow_plugin_base::cache::instance_budget_bytes());
+    assert!(instance.managers.budget_bytes() < base.manager_cache.lock().budget_bytes());
+
+    let mut cache = ManagerCache::new("Test", size * 2);
+    cache.put("a".into(), stab.clone());
+    cache.put("b".into(), stab.clone());
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("c").is_none());
+
+    // "b" is the least recently used
+    cache.put("c".into(), stab.clone());
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.contains("b"));
+    assert_eq!(cache.used_bytes(), size * 2);
+    assert_eq!(cache.stats(), gyroflow_plugin_base::cache::CacheStats { hits: 1, misses: 1, evictions: 1 });
+
+    // The newest entry is kept even if it doesn't fit
+    let mut cache = ManagerCache::new("Test", 0);
+    cache.put("a".into(), stab.clone());
+    cache.put("b".into(), stab);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.contains("b"));
+}
@@ -0,0 +1,107 @@
+// Fixtures shared by the integration tests: a synthetic Gyroflow project and an instance which loads it synchronously.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+
+pub const FPS: f64 = 30.0;
+pub const NUM_FRAMES: usize = 60;
+
+pub struct TempDir(pub std::path::PathBuf);
+impl TempDir {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("gyroflow-plugin-test-{}", fastrand::u64(..)));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn synthetic_project(dir: &TempDir) -> String {
+    // Slow constant rotation around one axis, 1 kHz
+    let raw_imu = (0..(NUM_FRAMES as f64 / FPS * 1000.0) as usize).map(|i| serde_json::json!({
+        "timestamp_ms": i as f64,
+        "gyro": [0.0, 0.0, 5.0 * (i as f64 / 100.0).sin()],
+        "accl": [0.0, 0.0, 1.0]
+    })).collect::<Vec<_>>();
+
+    let project = serde_json::json!({
+        "title": "Gyroflow data file",
+        "version": 3,
+        "app_version": "1.6.0",
+        "videofile": filesystem::path_to_url(&dir.0.join("clip.mp4").to_string_lossy()),
+        "video_info": {
+            "width": 1920,
+            "height": 1080,
+            "rotation": 0,
+            "num_frames": NUM_FRAMES,
+            "fps": FPS,
+            "duration_ms": NUM_FRAMES as f64 / FPS * 1000.0,
+            "vfr_fps": FPS,
+            "vfr_duration_ms": NUM_FRAMES as f64 / FPS * 1000.0
+        },
+        "stabilization": {
+            "fov": 1.25,
+            "method": "Default",
+            "smoothing_params": [ { "name": "smoothness", "value": 0.7 } ],
+            "frame_readout_time": 0.0,
+            "adaptive_zoom_window": 4.0,
+            "lens_correction_amount": 1.0,
+            "horizon_lock_amount": 0.0,
+            "horizon_lock_roll": 0.0,
+            "video_speed": 1.0,
+            "max_zoom": 150.0
+        },
+        "gyro_source": {
+            "filepath": "",
+            "imu_orientation": "XYZ",
+            "integration_method": 1,
+            "raw_imu": raw_imu
+        },
+        "offsets": { },
+        "output": {
+            "output_width": 1920,
+            "output_height": 1080
+        }
+    });
+    let path = dir.0.join("clip.gyroflow");
+    std::fs::write(&path, serde_json::to_string_pretty(&project).unwrap()).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+pub fn edit_project(path: &str, f: impl FnOnce(&mut serde_json::Value)) {
+    let mut project: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    f(&mut project);
+    std::fs::write(path, serde_json::to_string_pretty(&project).unwrap()).unwrap();
+}
+
+pub fn load(params: &mut MemoryParams, path: &str) -> (GyroflowPluginBase, GyroflowPluginBaseInstance, PluginResult<Arc<StabilizationManager>>) {
+    load_instance(params, path, true)
+}
+
+/// Loads like an instance restored from the host's project, which doesn't read the values from the Gyroflow project
+pub fn load_restored(params: &mut MemoryParams, path: &str) -> (GyroflowPluginBase, GyroflowPluginBaseInstance, PluginResult<Arc<StabilizationManager>>) {
+    load_instance(params, path, false)
+}
+
+fn load_instance(params: &mut MemoryParams, path: &str, reload_values_from_project: bool) -> (GyroflowPluginBase, GyroflowPluginBaseInstance, PluginResult<Arc<StabilizationManager>>) {
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    instance.background_loading = false;
+    instance.reload_values_from_project = reload_values_from_project;
+    params.set_string(Params::ProjectPath, path).unwrap();
+    let result = instance.stab_manager(params, &base.manager_cache, (1920, 1080), false);
+    (base, instance, result)
+}
+
+pub fn status_history(changes: &[ParamChange]) -> Vec<String> {
+    changes.iter().filter_map(|x| match x {
+        ParamChange::Value(Params::Status, ParamValue::String(s)) => Some(s.clone()),
+        _ => None
+    }).collect()
+}
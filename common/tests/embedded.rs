@@ -0,0 +1,57 @@
+// Encoding of the data embedded in the host's project file.
+
+use gyroflow_plugin_base::{ GyroflowPluginParams, Params };
+use gyroflow_plugin_base::embedded::*;
+use gyroflow_plugin_base::memory_params::MemoryParams;
+
+fn project_json() -> String {
+    let raw_imu = (0..5000).map(|i| serde_json::json!({
+        "timestamp_ms": i as f64,
+        "gyro": [0.0, (i as f64 / 50.0).cos(), (i as f64 / 100.0).sin()],
+        "accl": [0.0, 0.0, 1.0]
+    })).collect::<Vec<_>>();
+    serde_json::json!({ "title": "Gyroflow data file ąę 🎥", "gyro_source": { "raw_imu": raw_imu } }).to_string()
+}
+
+#[test]
+fn round_trip() {
+    let data = project_json();
+    let encoded = encode(&data);
+    assert!(is_encoded(&encoded));
+    assert!(encoded.len() * 4 < data.len(), "{} -> {}", data.len(), encoded.len());
+    assert!(encoded[MAGIC.len()..].bytes().all(|x| x.is_ascii_alphanumeric() || b"+/=".contains(&x)));
+    assert_eq!(decode(&encoded).unwrap(), data);
+
+    // Already encoded and empty values are unchanged
+    assert_eq!(encode(&encoded), encoded);
+    assert_eq!(encode(""), "");
+    assert_eq!(decode("").unwrap(), "");
+}
+
+#[test]
+fn plain_json_is_accepted() {
+    let data = r#"{ "calibrated_by": "test", "plugin_disable_stretch": true }"#;
+    assert!(!is_encoded(data));
+    assert_eq!(decode(data).unwrap(), data);
+}
+
+#[test]
+fn corrupted_data_is_an_error() {
+    assert!(decode(&format!("{MAGIC}not base64!")).is_err());
+    assert!(decode(&format!("{MAGIC}AAAA")).is_err());
+}
+
+#[test]
+fn params_round_trip() {
+    let data = project_json();
+    let mut params = MemoryParams::default();
+    for &param in PARAMS {
+        set(&mut params, param, &data).unwrap();
+        assert!(is_encoded(&params.get_string(param).unwrap()));
+        assert_eq!(get(&params, param).unwrap(), data);
+    }
+
+    // Values saved by older versions
+    params.set_string(Params::EmbeddedPreset, "{}").unwrap();
+    assert_eq!(get(&params, Params::EmbeddedPreset).unwrap(), "{}");
+}
@@ -0,0 +1,220 @@
+
+// Runs the shared plugin flow against the in-memory parameter host, without a video editor and without a GPU.
+
+mod common;
+
+use std::sync::Arc;
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn defaults_follow_param_definitions() {
+    let params = MemoryParams::default();
+    assert_eq!(params.get_f64(Params::Smoothness).unwrap(), 50.0);
+    assert_eq!(params.get_f64(Params::OutputWidth).unwrap(), 3840.0);
+    assert_eq!(params.get_bool(Params::StabilizationSpeedRamp).unwrap(), true);
+    assert_eq!(params.get_i32(Params::IntegrationMethod).unwrap(), 2); // VQF
+    assert_eq!(params.get_i32(Params::Interpolation).unwrap(), 0);
+    assert_eq!(params.get_string(Params::ProjectPath).unwrap(), "");
+    assert_eq!(params.label(Params::Browse), Some("Browse"));
+    assert!(params.changes.is_empty());
+}
+
+#[test]
+fn wrong_parameter_type_is_an_error() {
+    let mut params = MemoryParams::default();
+    assert!(params.get_bool(Params::Smoothness).is_err());
+    assert!(params.set_string(Params::Fov, "1").is_err());
+    assert!(params.set_f64_at_time(Params::ProjectPath, TimeType::Frame(0.0), 1.0).is_err());
+    assert!(params.changes.is_empty());
+}
+
+#[test]
+fn keyframes_are_interpolated_and_recorded() {
+    let mut params = MemoryParams::new(FPS);
+    assert!(!params.is_keyframed(Params::Fov));
+    params.set_f64_at_time(Params::Fov, TimeType::Frame(0.0), 1.0).unwrap();
+    params.set_f64_at_time(Params::Fov, TimeType::Milliseconds(1000.0), 2.0).unwrap();
+    assert!(params.is_keyframed(Params::Fov));
+
+    assert_eq!(params.get_f64_at_time(Params::Fov, TimeType::Frame(15.0)).unwrap(), 1.5);
+    assert_eq!(params.get_f64_at_time(Params::Fov, TimeType::Microseconds(250_000)).unwrap(), 1.25);
+    assert_eq!(params.get_f64_at_time(Params::Fov, TimeType::FrameOrMicrosecond((Some(90.0), None))).unwrap(), 2.0);
+    assert_eq!(params.get_keyframes(Params::Fov).len(), 2);
+
+    params.clear_keyframes(Params::Fov).unwrap();
+    assert!(!params.is_keyframed(Params::Fov));
+    assert_eq!(params.get_f64_at_time(Params::Fov, TimeType::Frame(15.0)).unwrap(), 1.0);
+
+    assert_eq!(params.take_changes(), vec![
+        ParamChange::Keyframe(Params::Fov, 0, 1.0),
+        ParamChange::Keyframe(Params::Fov, 1_000_000, 2.0),
+        ParamChange::ClearKeyframes(Params::Fov),
+    ]);
+    assert!(params.changes.is_empty());
+}
+
+#[test]
+fn update_loaded_state_toggles_params() {
+    let mut params = MemoryParams::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+
+    instance.update_loaded_state(&mut params, false);
+    assert!(!params.is_enabled(Params::Smoothness));
+    assert!(!params.is_enabled(Params::OutputWidth));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Project not loaded");
+    assert_eq!(params.label(Params::OpenGyroflow), Some("Open Gyroflow"));
+
+    instance.update_loaded_state(&mut params, true);
+    assert!(params.is_enabled(Params::Smoothness));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "OK");
+    assert_eq!(params.label(Params::OpenGyroflow), Some("Open in Gyroflow"));
+}
+
+#[test]
+fn cache_keyframes_uses_host_keyframes() {
+    let mut params = MemoryParams::new(FPS);
+    params.set_f64(Params::ZoomLimit, 140.0).unwrap();
+    params.set_f64_at_time(Params::Smoothness, TimeType::Frame(0.0), 20.0).unwrap();
+    params.set_f64_at_time(Params::Smoothness, TimeType::Frame(30.0), 80.0).unwrap();
+
+    for every_frame in [true, false] {
+        let mut instance = GyroflowPluginBaseInstance::default();
+        instance.cache_keyframes_every_frame = every_frame;
+        instance.cache_keyframes(&params, true, NUM_FRAMES, FPS);
+
+        let kparams = instance.keyframable_params.read();
+        assert!(kparams.use_gyroflows_keyframes);
+        let smoothness = |ms: f64| kparams.cached_keyframes.value_at_video_timestamp(&KeyframeType::SmoothingParamSmoothness, ms).unwrap();
+        assert!((smoothness(0.0)    - 0.2).abs() < 1e-6);
+        assert!((smoothness(500.0)  - 0.5).abs() < 1e-6);
+        assert!((smoothness(1000.0) - 0.8).abs() < 1e-6);
+        assert_eq!(kparams.cached_keyframes.value_at_video_timestamp(&KeyframeType::MaxZoom, 500.0), Some(140.0));
+    }
+}
+
+#[test]
+fn empty_path_is_not_loaded() {
+    let mut params = MemoryParams::default();
+    let (_base, _instance, result) = load(&mut params, "");
+    assert!(result.is_err());
+    assert!(!params.is_enabled(Params::Fov));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Project not loaded");
+}
+
+#[test]
+fn missing_project_is_not_loaded() {
+    let dir = TempDir::new();
+    let mut params = MemoryParams::default();
+    let (base, instance, result) = load(&mut params, &dir.0.join("missing.gyroflow").to_string_lossy());
+    assert!(result.is_err());
+    assert!(!params.is_enabled(Params::Fov));
+    assert!(instance.managers.is_empty());
+    assert!(base.manager_cache.lock().is_empty());
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Project file not found!");
+}
+
+#[test]
+fn loads_synthetic_project() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    let stab = result.unwrap();
+
+    assert_eq!(instance.num_frames, NUM_FRAMES);
+    assert_eq!(instance.fps, FPS);
+    assert_eq!(instance.original_video_size, (1920, 1080));
+    assert!(!instance.reload_values_from_project);
+
+    // Values are read from the project
+    assert_eq!(params.get_f64(Params::Fov).unwrap(), 1.25);
+    assert!((params.get_f64(Params::Smoothness).unwrap() - 70.0).abs() < 1e-6);
+    assert_eq!(params.get_f64(Params::ZoomLimit).unwrap(), 150.0);
+    assert_eq!(params.get_f64(Params::LensCorrectionStrength).unwrap(), 100.0);
+    assert_eq!(params.get_string(Params::LoadedProject).unwrap(), "clip.gyroflow");
+    assert_eq!(params.get_string(Params::Status).unwrap(), "OK");
+    assert!(params.is_enabled(Params::Smoothness));
+
+    // Manager is cached globally and per instance
+    assert_eq!(instance.managers.len(), 1);
+    assert_eq!(base.manager_cache.lock().len(), 1);
+    let again = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert!(Arc::ptr_eq(&stab, &again));
+    assert_eq!(stab.params.read().output_size, (1920, 1080));
+
+    // Lens profiles are shared by every manager
+    assert!(Arc::ptr_eq(&stab.lens_profile_db, &lens_db::shared()));
+}
+
+#[test]
+fn param_change_recalculates() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    result.unwrap();
+    let instance_id = params.get_string(Params::InstanceId).unwrap();
+    params.take_changes();
+
+    params.set_f64(Params::Smoothness, 30.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::Smoothness, true).unwrap();
+
+    let changes = params.take_changes();
+    assert_eq!(status_history(&changes), ["Calculating...", "OK"]);
+    // First user change makes the instance unique and drops the shared manager
+    assert!(instance.ever_changed);
+    assert_ne!(params.get_string(Params::InstanceId).unwrap(), instance_id);
+    assert!(instance.managers.is_empty());
+
+    let value = instance.keyframable_params.read().cached_keyframes.value_at_video_timestamp(&KeyframeType::SmoothingParamSmoothness, 0.0);
+    assert_eq!(value, Some(0.3));
+
+    // A new manager is created for the new instance id and keeps the user's value
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(params.get_f64(Params::Smoothness).unwrap(), 30.0);
+    assert_eq!(instance.managers.len(), 1);
+
+    // Changes without user interaction are ignored
+    params.set_f64(Params::Smoothness, 40.0).unwrap();
+    params.take_changes();
+    instance.param_changed(&mut params, &base.manager_cache, Params::Smoothness, false).unwrap();
+    assert!(status_history(&params.take_changes()).is_empty());
+    drop(stab);
+}
+
+#[test]
+fn output_size_changes() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    let stab = result.unwrap();
+
+    params.set_f64(Params::OutputWidth, 1000.0).unwrap();
+    params.set_f64(Params::OutputHeight, 500.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::OutputSizeSwap, true).unwrap();
+    assert_eq!(params.get_f64(Params::OutputWidth).unwrap(), 500.0);
+    assert_eq!(params.get_f64(Params::OutputHeight).unwrap(), 1000.0);
+    assert_eq!(stab.params.read().output_size, (500, 1000));
+
+    instance.param_changed(&mut params, &base.manager_cache, Params::OutputSizeToTimeline, true).unwrap();
+    assert_eq!(params.get_f64(Params::OutputWidth).unwrap(), 1920.0);
+    assert_eq!(params.get_f64(Params::OutputHeight).unwrap(), 1080.0);
+}
+
+#[test]
+fn project_path_change_clears_managers() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+
+    instance.reload_values_from_project = false;
+    instance.param_changed(&mut params, &base.manager_cache, Params::ProjectPath, true).unwrap();
+    assert!(instance.reload_values_from_project);
+    assert!(instance.managers.is_empty());
+    assert!(base.manager_cache.lock().is_empty());
+}
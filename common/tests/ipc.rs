@@ -0,0 +1,119 @@
+// Live link with the Gyroflow app, with the app side played by `Peer`.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::time::Duration;
+use gyroflow_plugin_base::{ GyroflowPluginParams, Params, ipc };
+use gyroflow_plugin_base::ipc::*;
+use gyroflow_plugin_base::memory_params::MemoryParams;
+use common::*;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn messages() -> Vec<Message> {
+    vec![
+        Message::Params { project: "/clips/clip.gyroflow".into(), values: BTreeMap::from([(Params::Fov, 1.5), (Params::Smoothness, 30.0)]) },
+        Message::SyncPoints { project: "/clips/clip.gyroflow".into(), offsets: vec![(1_000_000, -12.5), (5_000_000, -13.0)] },
+    ]
+}
+
+fn round_trip(link: Link, rx: mpsc::Receiver<Message>) {
+    let dir = link.path().parent().unwrap().to_path_buf();
+    assert_eq!(Peer::discover(&dir), vec![link.path().to_path_buf()]);
+
+    let mut peer = Peer::connect(link.path()).unwrap();
+    for msg in messages() {
+        peer.send(&msg).unwrap();
+        assert_eq!(rx.recv_timeout(TIMEOUT).unwrap(), msg);
+    }
+
+    let frame = Message::CurrentFrame { project: "/clips/clip.gyroflow".into(), timestamp_us: 33_333 };
+    link.send(&frame).unwrap();
+    assert_eq!(peer.recv_timeout(TIMEOUT), Some(frame));
+    assert_eq!(peer.recv_timeout(Duration::from_millis(100)), None);
+
+    let path = link.path().to_path_buf();
+    drop(link);
+    assert!(!path.exists());
+}
+
+#[test]
+fn files_round_trip() {
+    let dir = TempDir::new();
+    let (tx, rx) = mpsc::channel();
+    let tx = std::sync::Mutex::new(tx);
+    let link = Link::listen_files(&dir.0, move |x| { let _ = tx.lock().unwrap().send(x); }).unwrap();
+    assert!(link.path().is_dir());
+    round_trip(link, rx);
+}
+
+#[cfg(unix)]
+#[test]
+fn socket_round_trip() {
+    let dir = TempDir::new();
+    let (tx, rx) = mpsc::channel();
+    let tx = std::sync::Mutex::new(tx);
+    let link = Link::listen_socket(&dir.0, move |x| { let _ = tx.lock().unwrap().send(x); }).unwrap();
+    assert!(!link.path().is_dir());
+    round_trip(link, rx);
+}
+
+#[cfg(unix)]
+#[test]
+fn app_which_doesnt_read_doesnt_block_sending() {
+    let dir = TempDir::new();
+    let link = Link::listen_socket(&dir.0, |_| { }).unwrap();
+    let _peer = Peer::connect(link.path()).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    // Far more than the socket buffer holds
+    let started = std::time::Instant::now();
+    for timestamp_us in 0..100_000 {
+        link.send(&Message::CurrentFrame { project: "/clips/clip.gyroflow".into(), timestamp_us }).unwrap();
+    }
+    assert!(started.elapsed() < TIMEOUT);
+}
+
+#[test]
+fn messages_are_tagged_json() {
+    let json = serde_json::to_value(&messages()[0]).unwrap();
+    assert_eq!(json["type"], "params");
+    assert_eq!(json["project"], "/clips/clip.gyroflow");
+    for msg in messages() {
+        assert_eq!(serde_json::from_str::<Message>(&serde_json::to_string(&msg).unwrap()).unwrap(), msg);
+    }
+}
+
+// Uses the listener of the process, so it's the only test in this file which starts it
+#[test]
+fn gyroflow_app_changes_are_applied() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    ipc::start(&dir.0.join("ipc")).unwrap();
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    let stab = result.unwrap();
+
+    let link = ipc::Peer::discover(&dir.0.join("ipc")).pop().unwrap();
+    let mut app = ipc::Peer::connect(&link).unwrap();
+    // Only the adjustment values are taken, other parameters can't be set to a number
+    app.send(&ipc::Message::Params { project: path.clone(), values: [(Params::Fov, 1.7), (Params::ToggleOverview, 1.0), (Params::ZoomMode, 0.0)].into() }).unwrap();
+    app.send(&ipc::Message::SyncPoints { project: path.clone(), offsets: vec![(500_000, -20.0)] }).unwrap();
+
+    let start = std::time::Instant::now();
+    while stab.gyro.read().get_offsets().is_empty() || ipc::params_update(&path, 0).is_none() {
+        assert!(start.elapsed() < std::time::Duration::from_secs(5), "Messages not received");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(stab.gyro.read().get_offsets().get(&500_000).copied(), Some(-20.0));
+    assert_eq!(ipc::params_update(&path, 0).unwrap().1, [(Params::Fov, 1.7)].into());
+
+    instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(params.get_f64(Params::Fov).unwrap(), 1.7);
+    assert!(ipc::params_update(&path, instance.ipc_generation).is_none());
+
+    ipc::report_frame(&stab, 100_000);
+    assert_eq!(app.recv_timeout(std::time::Duration::from_secs(5)), Some(ipc::Message::CurrentFrame { project: path.clone(), timestamp_us: 100_000 }));
+}
@@ -0,0 +1,82 @@
+// Loading projects in the background, and waiting for them in final renders.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn background_loading_passes_through_until_ready() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    let mut params = MemoryParams::new(FPS);
+    params.set_string(Params::ProjectPath, &path).unwrap();
+
+    let err = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap_err();
+    assert!(matches!(err.downcast_ref::<PluginError>(), Some(PluginError::Loading)));
+    assert!(instance.is_loading());
+    assert!(params.get_string(Params::Status).unwrap().starts_with("Loading..."));
+
+    let start = std::time::Instant::now();
+    let stab = loop {
+        match instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false) {
+            Ok(stab) => break stab,
+            Err(_) => assert!(instance.is_loading()),
+        }
+        assert!(start.elapsed().as_secs() < 30, "Project wasn't loaded in time");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+    assert!(!instance.is_loading());
+    assert_eq!(instance.num_frames, NUM_FRAMES);
+    assert_eq!(params.get_string(Params::Status).unwrap(), "OK");
+    assert_eq!(base.manager_cache.lock().len(), 1);
+    drop(stab);
+}
+
+#[test]
+fn background_loading_keeps_the_error() {
+    let dir = TempDir::new();
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    let mut params = MemoryParams::new(FPS);
+    params.set_string(Params::ProjectPath, &dir.0.join("missing.gyroflow").to_string_lossy()).unwrap();
+
+    let start = std::time::Instant::now();
+    while instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).is_err() && instance.is_loading() {
+        assert!(start.elapsed().as_secs() < 30, "Loading didn't finish in time");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Project file not found!");
+
+    // The failed load isn't restarted on every frame, only after a reload
+    assert!(instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).is_err());
+    assert!(!instance.is_loading());
+    instance.clear_stab(&base.manager_cache);
+    assert!(instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).is_err());
+    assert!(instance.is_loading());
+}
+
+#[test]
+fn final_renders_wait_for_the_background_load() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    let mut params = MemoryParams::new(FPS);
+    params.set_string(Params::ProjectPath, &path).unwrap();
+
+    instance.set_interactive_render(true);
+    assert!(instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).is_err());
+    assert!(instance.is_loading());
+    assert!(instance.pass_through_on_error(&PluginError::Loading));
+
+    // An export started while the viewer is loading the project gets the stabilized frame
+    instance.set_interactive_render(false);
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert!(!instance.is_loading());
+    assert_eq!(instance.num_frames, NUM_FRAMES);
+    drop(stab);
+}
@@ -0,0 +1,23 @@
+// Notification sinks.
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+
+#[test]
+fn status_sink_writes_the_status() {
+    use gyroflow_plugin_base::notification::*;
+    let mut params = MemoryParams::default();
+    let e = PluginError::PresetInvalid("bad json".into());
+    StatusSink.notify(Some(&mut params), &Notification::from(&e));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Invalid preset!");
+    assert_eq!(params.hint(Params::Status), Some("Failed to load preset: bad json"));
+
+    // Without parameters the message is only logged
+    StatusSink.notify(None, &Notification::new(Severity::Error, "Unable to start Gyroflow"));
+    LogSink.notify(Some(&mut params), &Notification::new(Severity::Info, "Info"));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Invalid preset!");
+
+    // Sinks which can't ask show the message, and don't open anything
+    assert!(!StatusSink.ask(Some(&mut params), &Notification::new(Severity::Info, "Project saved to clip.gyroflow.").with_title("Project saved"), "Do you want to open it in Gyroflow?"));
+    assert_eq!(params.get_string(Params::Status).unwrap(), "Project saved");
+}
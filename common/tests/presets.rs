@@ -0,0 +1,65 @@
+// Presets saved by the plugin and by the Gyroflow app.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn presets_round_trip() {
+    let dir = TempDir::new();
+    let mut params = MemoryParams::new(FPS);
+    params.set_f64(Params::Smoothness, 35.0).unwrap();
+    params.set_f64(Params::ZoomLimit, 110.0).unwrap();
+    params.set_f64(Params::LensCorrectionStrength, 80.0).unwrap();
+    params.set_f64(Params::HorizonLockAmount, 100.0).unwrap();
+    params.set_f64(Params::AdditionalPitch, 5.0).unwrap();
+    params.set_i32(Params::IntegrationMethod, 4).unwrap();
+    params.set_i32(Params::Interpolation, 2).unwrap();
+
+    let preset = presets::from_params(&params, "Drone").unwrap();
+    assert_eq!(preset["stabilization"]["smoothing_params"][0]["value"], 0.35);
+    assert_eq!(preset["stabilization"]["lens_correction_amount"], 0.8);
+    assert_eq!(preset["stabilization"]["interpolation"], "Bilinear");
+    assert_eq!(preset["gyro_source"]["integration_method"], 4);
+    assert!(preset.get("plugin_disable_stretch").is_none());
+
+    let path = presets::save(&preset, &dir.0.join("Drone")).unwrap();
+    presets::save(&preset, &dir.0.join("action cam.gyroflow")).unwrap();
+    assert_eq!(path, dir.0.join("Drone.gyroflow"));
+    assert_eq!(presets::list_in(&dir.0).into_iter().map(|x| x.0).collect::<Vec<_>>(), ["action cam", "Drone"]);
+    // The options and the list the selected index is resolved with don't change after the parameter was registered
+    let options = presets::options();
+    assert_eq!(options[1..], presets::listed().iter().map(|x| x.0.clone()).collect::<Vec<_>>()[..]);
+    assert_eq!(presets::options(), options);
+
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    let mut loaded = MemoryParams::new(FPS);
+    instance.load_preset(&mut loaded, &base.manager_cache, &path).unwrap();
+    for param in [Params::Smoothness, Params::ZoomLimit, Params::LensCorrectionStrength, Params::HorizonLockAmount, Params::AdditionalPitch, Params::Fov] {
+        assert_eq!(loaded.get_f64(param).unwrap(), params.get_f64(param).unwrap(), "{param:?}");
+    }
+    assert_eq!(loaded.get_i32(Params::IntegrationMethod).unwrap(), 4);
+    assert_eq!(loaded.get_i32(Params::Interpolation).unwrap(), 2);
+    assert_eq!(loaded.get_string(Params::LoadedPreset).unwrap(), "Drone");
+    assert!(embedded::get(&loaded, Params::EmbeddedPreset).unwrap().contains("\"Drone\""));
+    // Watched with the project, and loaded again when it changes
+    assert_eq!(instance.preset_path, path.to_string_lossy());
+}
+
+#[test]
+fn presets_from_the_app_only_change_their_values() {
+    let dir = TempDir::new();
+    let path = dir.0.join("lens only.gyroflow");
+    std::fs::write(&path, r#"{ "version": 3, "stabilization": { "fov": 1.2 }, "plugin_disable_stretch": true }"#).unwrap();
+
+    let mut params = MemoryParams::new(FPS);
+    params.set_f64(Params::Smoothness, 35.0).unwrap();
+    let changed = presets::apply(&mut params, &serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap()).unwrap();
+    assert_eq!(changed, [Params::Fov, Params::DisableStretch]);
+    assert_eq!(params.get_f64(Params::Smoothness).unwrap(), 35.0);
+    assert!(params.get_bool(Params::DisableStretch).unwrap());
+    assert_eq!(presets::load(&path).unwrap().0, "lens only");
+}
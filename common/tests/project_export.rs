@@ -0,0 +1,77 @@
+// Saving the embedded project data and the plugin values to .gyroflow files.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn embedded_project_data_is_extracted() {
+    let dir = TempDir::new();
+    let mut data: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(synthetic_project(&dir)).unwrap()).unwrap();
+    data["plugin_disable_stretch"] = true.into();
+    let mut params = MemoryParams::new(FPS);
+    params.set_string(Params::ProjectPath, "D:/Projects/clip.mp4").unwrap();
+    embedded::set(&mut params, Params::ProjectData, &data.to_string()).unwrap();
+
+    let mut instance = GyroflowPluginBaseInstance::default();
+    instance.background_loading = false;
+    let target = dir.0.join("extracted.gyroflow");
+    instance.extract_project_data(&mut params, &target).unwrap();
+
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap()).unwrap();
+    assert!(saved.get("plugin_disable_stretch").is_none());
+    assert_eq!(saved["stabilization"]["fov"], 1.25);
+    assert!(params.get_bool(Params::DisableStretch).unwrap());
+    assert_eq!(params.get_string(Params::ProjectPath).unwrap(), target.to_string_lossy());
+
+    // Without embedded data there's nothing to extract
+    params.set_string(Params::ProjectData, "").unwrap();
+    assert!(instance.extract_project_data(&mut params, &dir.0.join("empty.gyroflow")).is_err());
+    assert!(!dir.0.join("empty.gyroflow").exists());
+}
+
+#[test]
+fn plugin_values_are_exported_to_the_project() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (_base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+
+    params.set_f64(Params::Fov, 1.5).unwrap();
+    params.set_f64(Params::HorizonLockAmount, 80.0).unwrap();
+    params.set_f64_at_time(Params::Smoothness, TimeType::Frame(0.0), 20.0).unwrap();
+    params.set_f64_at_time(Params::Smoothness, TimeType::Frame(30.0), 60.0).unwrap();
+    instance.cache_keyframes(&params, false, NUM_FRAMES, FPS);
+    let original = std::fs::read_to_string(&path).unwrap();
+    let target = dir.0.join("clip_plugin.gyroflow").to_string_lossy().to_string();
+    assert_eq!(instance.export_project(&mut params).unwrap(), target);
+    assert_eq!(params.get_string(Params::ProjectPath).unwrap(), target);
+
+    // The loaded project and the shared manager are unchanged
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    let live = instance.managers.peek_lru().unwrap().1.clone();
+    assert!(!live.keyframes.read().is_keyframed_internally(&KeyframeType::SmoothingParamSmoothness));
+
+    // The app's data is kept
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap()).unwrap();
+    let original: serde_json::Value = serde_json::from_str(&original).unwrap();
+    assert_eq!(saved["output"], original["output"]);
+
+    // A project saved by the plugin is overwritten on the next export
+    assert_eq!(project_export::target_path(&target), target);
+    assert_eq!(project_export::target_path("D:/Projects/clip.mp4"), std::path::Path::new("D:/Projects/clip_plugin.gyroflow").to_string_lossy());
+
+    // The saved project renders the same as the plugin, without the plugin's keyframe provider
+    let (_base, _instance, result) = load(&mut MemoryParams::new(FPS), &target);
+    let stab = result.unwrap();
+    assert_eq!(stab.params.read().fov, 1.5);
+    assert!(stab.smoothing.read().horizon_lock.lock_enabled);
+    assert_eq!(stab.smoothing.read().horizon_lock.horizonlockpercent, 80.0);
+    let keyframes = stab.keyframes.read();
+    assert!(keyframes.is_keyframed_internally(&KeyframeType::SmoothingParamSmoothness));
+    assert_eq!(keyframes.get_keyframes(&KeyframeType::SmoothingParamSmoothness).unwrap().len(), 2);
+    assert!(!keyframes.is_keyframed_internally(&KeyframeType::Fov));
+}
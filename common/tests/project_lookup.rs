@@ -0,0 +1,106 @@
+// Finding the project of the clip: remapped paths, sidecar projects and image sequences.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn project_path_is_remapped() {
+    use gyroflow_plugin_base::path_remap::*;
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mappings = [
+        PathMapping { from: "D:\\".into(),          to: "/nonexistent/".into() },
+        PathMapping { from: "d:\\projects\\".into(), to: format!("{}/", dir.0.to_string_lossy()) },
+    ];
+    let expected = format!("{}/clip.gyroflow", dir.0.to_string_lossy().replace('\\', "/"));
+    assert_eq!(apply("D:\\Projects\\clip.gyroflow", &mappings[1..]), Some(expected.clone()));
+    assert_eq!(resolve_with("D:\\Projects\\clip.gyroflow", "", &mappings[..1]), "D:\\Projects\\clip.gyroflow");
+    assert_eq!(resolve_with("D:\\Projects\\clip.gyroflow", "", &mappings[1..]), expected);
+    assert_eq!(resolve_with(&path, "", &[]), path);
+}
+
+#[test]
+fn project_is_found_next_to_the_media() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let media = dir.0.join("clip.mp4");
+    std::fs::write(&media, b"").unwrap();
+
+    let base = GyroflowPluginBase::default();
+    let mut instance = GyroflowPluginBaseInstance::default();
+    instance.background_loading = false;
+    instance.media_file_path = media.to_string_lossy().to_string();
+    let mut params = MemoryParams::new(FPS);
+    params.set_string(Params::ProjectPath, "D:\\Projects\\shoot\\clip.gyroflow").unwrap();
+    instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(params.get_string(Params::LoadedProject).unwrap(), path);
+    assert_eq!(params.get_string(Params::ProjectPath).unwrap(), "D:\\Projects\\shoot\\clip.gyroflow");
+}
+
+#[test]
+fn sidecar_projects_are_ranked() {
+    use gyroflow_plugin_base::sidecar::*;
+    let dir = TempDir::new();
+    let project = |name: &str, videofile: &str| {
+        let path = dir.0.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::json!({ "videofile": videofile }).to_string()).unwrap();
+        path
+    };
+    let video = dir.0.join("footage").join("clip.mp4").to_string_lossy().to_string();
+    let other      = project("footage/clip_other.gyroflow",      "file:///D:/footage/clip.mp4");
+    let stabilized = project("footage/clip_stabilized.gyroflow", "D:\\footage\\clip.mp4");
+    let exact      = project("projects/clip.gyroflow",           "");
+    project("footage/clip_v2.gyroflow", "file:///D:/footage/clip_v2.mp4");
+    project("footage/clip.mp4.json",    "clip.mp4");
+
+    let folders = vec![".".to_string(), "gyroflow".to_string(), "../projects".to_string()];
+    let found = find_projects_in(&video, &folders);
+    let paths = found.iter().map(|x| x.path.canonicalize().unwrap()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![exact.canonicalize().unwrap(), stabilized.canonicalize().unwrap(), other.canonicalize().unwrap()]);
+    assert_eq!(found[0].name_match, NameMatch::Exact);
+    assert_eq!(found[0].videofile_matches, None);
+    assert_eq!(found[1].name_match, NameMatch::KnownSuffix);
+    assert_eq!(found[2].videofile_matches, Some(true));
+
+    // Only the folder of the video
+    assert_eq!(find_projects_in(&video, &[".".to_string()]).len(), 2);
+}
+
+#[test]
+fn image_sequences_are_parsed() {
+    use gyroflow_plugin_base::sequence::*;
+    let dir = TempDir::new();
+    for i in 86400..86403 {
+        std::fs::write(dir.0.join(format!("A001_C002_{i:07}.dpx")), b"").unwrap();
+    }
+    let frame = dir.0.join("A001_C002_0086401.dpx").to_string_lossy().to_string();
+    let seq = ImageSequence::parse(&frame).unwrap();
+    assert_eq!((seq.prefix.as_str(), seq.suffix.as_str(), seq.padding), ("A001_C002_", ".dpx", 7));
+    assert_eq!(seq.frame_range(), Some((86400, 86402)));
+    assert_eq!(seq.base_name(), "A001_C002");
+
+    for notation in ["A001_C002_#######.dpx", "A001_C002_%07d.dpx", "A001_C002_[0086400-0086402].dpx"] {
+        let path = dir.0.join(notation).to_string_lossy().to_string();
+        let other = ImageSequence::parse(&path).unwrap();
+        assert!(other.matches(&seq), "{notation}");
+        assert_eq!(media_path(&path), dir.0.join("A001_C002_0086400.dpx").to_string_lossy());
+    }
+    assert_eq!(ImageSequence::parse("/footage/C0001.mp4"), None);
+    assert_eq!(media_path("/footage/C0001.mp4"), "/footage/C0001.mp4");
+
+    // Hosts which number the frames like the files render frame 86400 at timestamp 0
+    let pattern = dir.0.join("A001_C002_%07d.dpx").to_string_lossy().to_string();
+    assert_eq!(first_frame_of(&filesystem::path_to_url(&pattern)), Some(86400));
+    assert_eq!(first_frame_of(&filesystem::path_to_url("/footage/C0001.mp4")), None);
+
+    // The project is found for any notation
+    std::fs::write(dir.0.join("A001_C002.gyroflow"), serde_json::json!({ "videofile": "D:\\Footage\\A001_C002_%07d.dpx" }).to_string()).unwrap();
+    std::fs::write(dir.0.join("A001_C002_0086400.gyroflow"), serde_json::json!({ "videofile": "B001_%07d.dpx" }).to_string()).unwrap();
+    let project = dir.0.join("A001_C002.gyroflow").to_string_lossy().to_string();
+    assert_eq!(GyroflowPluginBase::get_project_paths(&frame), vec![project.clone()]);
+    assert_eq!(GyroflowPluginBase::get_project_path(&pattern), Some(project));
+}
@@ -0,0 +1,57 @@
+// Rolling shutter correction.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn rolling_shutter_is_read_and_changed() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut project: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    project["stabilization"]["frame_readout_time"] = serde_json::json!(20.5);
+    std::fs::write(&path, project.to_string()).unwrap();
+
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+    assert_eq!(params.get_f64(Params::FrameReadoutTime).unwrap(), 20.5);
+    assert_eq!(params.get_i32(Params::ReadoutDirection).unwrap(), 0);
+    assert!(params.is_enabled(Params::FrameReadoutTime));
+
+    params.set_f64(Params::FrameReadoutTime, 12.0).unwrap();
+    params.set_i32(Params::ReadoutDirection, 1).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::FrameReadoutTime, true).unwrap();
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(stab.params.read().frame_readout_time, 12.0);
+    assert_eq!(stab.params.read().frame_readout_direction, ReadoutDirection::BottomToTop);
+
+    params.set_f64(Params::FrameReadoutTime, 0.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::FrameReadoutTime, true).unwrap();
+    assert_eq!(stab.params.read().frame_readout_time, 0.0);
+}
+
+#[test]
+fn project_rolling_shutter_is_kept_until_changed() {
+    // Instances saved before the rolling shutter could be changed have the default `FrameReadoutTime` of 0
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    edit_project(&path, |x| x["stabilization"]["frame_readout_time"] = serde_json::json!(20.5));
+
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load_restored(&mut params, &path);
+    let stab = result.unwrap();
+    assert_eq!(params.get_f64(Params::FrameReadoutTime).unwrap(), 0.0);
+    assert_eq!(stab.params.read().frame_readout_time, 20.5);
+
+    params.set_i32(Params::ReadoutDirection, 2).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ReadoutDirection, true).unwrap();
+    assert_eq!(stab.params.read().frame_readout_direction, ReadoutDirection::LeftToRight);
+    assert_eq!(stab.params.read().frame_readout_time, 20.5);
+
+    params.set_f64(Params::FrameReadoutTime, 0.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::FrameReadoutTime, true).unwrap();
+    assert_eq!(stab.params.read().frame_readout_time, 0.0);
+}
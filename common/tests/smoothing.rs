@@ -0,0 +1,68 @@
+// Smoothing algorithm selection and the parameters of the algorithms.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn smoothing_algorithm_and_its_parameters() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+
+    let default = smoothing::selected(&params).unwrap();
+    assert_eq!(default.name, "Default");
+    assert!(smoothing::names().len() > 1);
+    assert_eq!(params.get_f64(Params::Smoothness).unwrap(), 70.0);
+    assert!(params.is_enabled(Params::Smoothness));
+    for (param, name) in smoothing::PARAMS {
+        assert_eq!(params.is_enabled(*param), default.parameters.iter().any(|x| x == name), "{param:?}");
+    }
+
+    params.set_bool(Params::PerAxis, true).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::PerAxis, true).unwrap();
+    params.set_f64(Params::SmoothnessPitch, 20.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SmoothnessPitch, true).unwrap();
+    let value = instance.keyframable_params.read().cached_keyframes.value_at_video_timestamp(&KeyframeType::SmoothingParamPitch, 0.0);
+    assert_eq!(value, Some(0.2));
+
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(stab.smoothing.read().current().get_name(), "Default");
+    assert_eq!(stab.smoothing.read().current().get_parameter("per_axis"), 1.0);
+
+    // Switching the algorithm enables its parameters and changes the manager's algorithm
+    let (index, other) = smoothing::algorithms().iter().enumerate().find(|x| x.1.name != "Default").unwrap();
+    params.set_i32(Params::SmoothingAlgorithm, index as i32).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SmoothingAlgorithm, true).unwrap();
+    assert_eq!(stab.smoothing.read().current().get_name(), other.name);
+    for (param, name) in smoothing::PARAMS {
+        assert_eq!(params.is_enabled(*param), other.parameters.iter().any(|x| x == name), "{param:?}");
+    }
+    assert_eq!(presets::from_params(&params, "other").unwrap()["stabilization"]["method"], other.name.as_str());
+}
+
+#[test]
+fn project_algorithm_is_kept_until_selected() {
+    // Instances saved before the algorithm could be selected have the default `SmoothingAlgorithm`
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let other = smoothing::algorithms().iter().find(|x| x.name != "Default").unwrap();
+    edit_project(&path, |x| x["stabilization"]["method"] = other.name.as_str().into());
+
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load_restored(&mut params, &path);
+    let stab = result.unwrap();
+    assert_eq!(smoothing::selected(&params).unwrap().name, "Default");
+    assert_eq!(stab.smoothing.read().current().get_name(), other.name);
+    assert!(!overrides::is_set(&params, Params::SmoothingAlgorithm));
+
+    let index = smoothing::algorithms().iter().position(|x| x.name == "Default").unwrap();
+    params.set_i32(Params::SmoothingAlgorithm, index as i32).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SmoothingAlgorithm, true).unwrap();
+    assert_eq!(stab.smoothing.read().current().get_name(), "Default");
+    assert!(overrides::is_set(&params, Params::SmoothingAlgorithm));
+}
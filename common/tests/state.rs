@@ -0,0 +1,64 @@
+// Decoding of `GyroflowPluginBaseInstance` saved by older releases.
+
+use gyroflow_plugin_base::GyroflowPluginBaseInstance;
+use gyroflow_plugin_base::state::*;
+
+fn hex(lines: &[&str]) -> Vec<u8> {
+    let s = lines.concat();
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+// `GyroflowPluginBaseInstance` in the unversioned layout
+const V1_BASE_INSTANCE: &[&str] = &[
+    "00000f000000000000700800000000000080070000000000003804000000000000800700000000000038040000000000",
+    "002c01000000000000b81e85eb51f83d4001010100000100",
+];
+
+fn check(base: &GyroflowPluginBaseInstance) {
+    assert!(!base.reload_values_from_project);
+    assert_eq!(base.original_video_size, (3840, 2160));
+    assert_eq!(base.original_output_size, (1920, 1080));
+    assert_eq!(base.timeline_size, (1920, 1080));
+    assert_eq!(base.num_frames, 300);
+    assert_eq!(base.fps, 29.97);
+    assert!(base.has_motion);
+    assert!(base.ever_changed);
+    assert!(base.cache_keyframes_every_frame);
+    assert!(!base.framebuffer_inverted);
+    assert!(!base.anamorphic_adjust_size);
+    assert!(base.always_set_input_rotation);
+    assert!(!base.opencl_disabled);
+}
+
+#[test]
+fn v1_blob_is_decoded() {
+    let base = GyroflowPluginBaseInstance::from_blob(&VersionedBlob { version: 1, data: hex(V1_BASE_INSTANCE) }).unwrap();
+    check(&base);
+}
+
+#[test]
+fn current_layout_round_trip() {
+    let base = GyroflowPluginBaseInstance::from_blob(&VersionedBlob { version: 1, data: hex(V1_BASE_INSTANCE) }).unwrap();
+    let blob = base.to_blob().unwrap();
+    assert_eq!(blob.version, GyroflowPluginBaseInstance::VERSION);
+    check(&GyroflowPluginBaseInstance::from_blob(&blob).unwrap());
+}
+
+#[test]
+fn current_layout_matches_latest_frozen_layout() {
+    // If this fails, a serialized field was changed without adding a new layout version
+    let base = GyroflowPluginBaseInstance::from_blob(&VersionedBlob { version: 1, data: hex(V1_BASE_INSTANCE) }).unwrap();
+    assert_eq!(encode(&base).unwrap(), hex(V1_BASE_INSTANCE));
+}
+
+#[test]
+fn newer_layout_is_an_error() {
+    let blob = VersionedBlob { version: GyroflowPluginBaseInstance::VERSION + 1, data: hex(V1_BASE_INSTANCE) };
+    assert!(GyroflowPluginBaseInstance::from_blob(&blob).is_err());
+}
+
+#[test]
+fn truncated_blob_is_an_error() {
+    let data = hex(V1_BASE_INSTANCE);
+    assert!(GyroflowPluginBaseInstance::from_blob(&VersionedBlob { version: 1, data: data[..10].to_vec() }).is_err());
+}
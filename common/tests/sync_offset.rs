@@ -0,0 +1,46 @@
+// Sync offset adjustment on top of the project offsets.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn sync_offset_adjusts_the_project_offsets() {
+    let project: sync_offset::Offsets = [(1_000_000, -10.0), (3_000_000, -20.0)].into();
+    assert_eq!(sync_offset::combine(&project, &Default::default()), project);
+    assert_eq!(sync_offset::combine(&project, &[(0, 5.0)].into()), [(1_000_000, -5.0), (3_000_000, -15.0)].into());
+    assert_eq!(sync_offset::combine(&Default::default(), &[(0, 5.0)].into()), [(0, 5.0)].into());
+    // Drift: the adjustment is interpolated between its keyframes, and added at each of them
+    let drift = sync_offset::combine(&project, &[(0, 0.0), (2_000_000, 4.0)].into());
+    assert_eq!(drift, [(0, -10.0), (1_000_000, -8.0), (2_000_000, -11.0), (3_000_000, -16.0)].into());
+
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+
+    params.set_f64(Params::SyncOffsetMs, 15.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SyncOffsetMs, true).unwrap();
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(stab.gyro.read().get_offsets().clone(), [(0, 15.0)].into());
+
+    params.set_f64(Params::SyncOffsetMs, -7.5).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SyncOffsetMs, true).unwrap();
+    assert_eq!(stab.gyro.read().get_offsets().clone(), [(0, -7.5)].into());
+    params.set_f64(Params::SyncOffsetMs, 0.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SyncOffsetMs, true).unwrap();
+    assert!(stab.gyro.read().get_offsets().is_empty());
+
+    // Exporting writes the adjusted offsets and resets the adjustment
+    params.set_f64(Params::SyncOffsetMs, 3.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::SyncOffsetMs, true).unwrap();
+    instance.cache_keyframes(&params, false, NUM_FRAMES, FPS);
+    let target = instance.export_project(&mut params).unwrap();
+    assert_eq!(params.get_f64(Params::SyncOffsetMs).unwrap(), 0.0);
+    assert_eq!(stab.gyro.read().get_offsets().clone(), [(0, 3.0)].into());
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&target).unwrap()).unwrap();
+    assert!(!saved["offsets"].as_object().unwrap().is_empty());
+}
@@ -0,0 +1,43 @@
+// Used range of the source media.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn trim_range_is_normalized_and_applied() {
+    assert_eq!(trim::normalize((500_000, 1_000_000), 2000.0), Some((0.25, 0.5)));
+    assert_eq!(trim::normalize((-100_000, 3_000_000), 2000.0), Some((0.0, 1.0)));
+    assert_eq!(trim::normalize((1_000_000, 1_000_000), 2000.0), None);
+    assert_eq!(trim::normalize((0, 1_000_000), 0.0), None);
+
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (_base, _instance, result) = load(&mut params, &path);
+    let stab = result.unwrap();
+    let duration_us = (stab.params.read().duration_ms * 1000.0).round() as i64;
+
+    assert!(trim::set_range(&stab, Some((0, duration_us / 2))));
+    assert_eq!(stab.trim_ranges(), vec![(0.0, 0.5)]);
+    assert_eq!(trim::range_us(&stab), Some((0, duration_us / 2)));
+    // Unchanged within a millisecond
+    assert!(!trim::set_range(&stab, Some((400, duration_us / 2 - 400))));
+
+    assert!(trim::set_range(&stab, None));
+    assert_eq!(trim::range_us(&stab), None);
+    assert!(!trim::set_range(&stab, Some((0, duration_us))));
+}
+
+#[test]
+fn whole_media_is_not_a_sub_range() {
+    assert!(trim::is_sub_range((500_000, 1_000_000), 2000.0));
+    assert!(trim::is_sub_range((0, 1_900_000), 2000.0));
+    assert!(!trim::is_sub_range((0, 2_000_000), 2000.0));
+    assert!(!trim::is_sub_range((-100_000, 3_000_000), 2000.0));
+    // Within a millisecond of the whole media
+    assert!(!trim::is_sub_range((400, 1_999_600), 2000.0));
+    assert!(!trim::is_sub_range((0, 1_000_000), 0.0));
+}
@@ -0,0 +1,93 @@
+// Zooming mode, speed and the position offset.
+
+mod common;
+
+use gyroflow_plugin_base::*;
+use gyroflow_plugin_base::memory_params::*;
+use common::*;
+
+#[test]
+fn position_offset_is_read_and_keyframed() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut project: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    project["stabilization"]["adaptive_zoom_center_offset"] = serde_json::json!([0.1, -0.2]);
+    std::fs::write(&path, project.to_string()).unwrap();
+
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+    assert!((params.get_f64(Params::PositionX).unwrap() - 10.0).abs() < 1e-9);
+    assert!((params.get_f64(Params::PositionY).unwrap() + 20.0).abs() < 1e-9);
+    assert!(params.is_enabled(Params::PositionX));
+
+    params.set_f64_at_time(Params::PositionX, TimeType::Frame(0.0), 0.0).unwrap();
+    params.set_f64_at_time(Params::PositionX, TimeType::Frame(30.0), 50.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::PositionX, true).unwrap();
+    let kparams = instance.keyframable_params.read();
+    let at = |ts_ms: f64| kparams.cached_keyframes.value_at_video_timestamp(&KeyframeType::ZoomingCenterX, ts_ms).unwrap();
+    assert_eq!(at(0.0), 0.0);
+    assert!((at(1000.0) - 0.5).abs() < 1e-9);
+    assert!(at(500.0) > 0.0 && at(500.0) < 0.5);
+    assert_eq!(kparams.cached_keyframes.value_at_video_timestamp(&KeyframeType::ZoomingCenterY, 500.0), Some(-0.2));
+}
+
+#[test]
+fn zooming_mode_and_speed() {
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load(&mut params, &path);
+    drop(result.unwrap());
+    assert_eq!(params.get_i32(Params::ZoomMode).unwrap(), ZOOM_DYNAMIC);
+    assert_eq!(params.get_f64(Params::ZoomSpeed).unwrap(), 4.0);
+    assert!(params.is_enabled(Params::ZoomSpeed));
+
+    params.set_i32(Params::ZoomMode, ZOOM_STATIC).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomMode, true).unwrap();
+    let stab = instance.stab_manager(&mut params, &base.manager_cache, (1920, 1080), false).unwrap();
+    assert_eq!(stab.params.read().adaptive_zoom_window, 0.0);
+    assert!(!params.is_enabled(Params::ZoomSpeed));
+    assert!(!params.is_enabled(Params::ZoomMethod));
+
+    params.set_i32(Params::ZoomMode, ZOOM_DYNAMIC).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomMode, true).unwrap();
+    params.set_f64(Params::ZoomSpeed, 1.5).unwrap();
+    params.set_i32(Params::ZoomMethod, 0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomSpeed, true).unwrap();
+    assert_eq!(stab.params.read().adaptive_zoom_window, 1.5);
+    assert_eq!(stab.params.read().adaptive_zoom_method as i32, 0);
+    assert!(params.is_enabled(Params::ZoomSpeed));
+
+    params.set_i32(Params::ZoomMode, ZOOM_DISABLED).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomMode, true).unwrap();
+    assert!(stab.params.read().adaptive_zoom_window < 0.0);
+    assert_eq!(zoom_mode(stab.params.read().adaptive_zoom_window), ZOOM_DISABLED);
+}
+
+#[test]
+fn project_zooming_is_kept_until_changed() {
+    // Instances saved before the zooming could be changed have the default `ZoomMode`, `ZoomSpeed` and `ZoomMethod`
+    let dir = TempDir::new();
+    let path = synthetic_project(&dir);
+    edit_project(&path, |x| {
+        x["stabilization"]["adaptive_zoom_window"] = serde_json::json!(0.0);
+        x["stabilization"]["adaptive_zoom_method"] = serde_json::json!(0);
+    });
+
+    let mut params = MemoryParams::new(FPS);
+    let (base, mut instance, result) = load_restored(&mut params, &path);
+    let stab = result.unwrap();
+    assert_eq!(params.get_i32(Params::ZoomMode).unwrap(), ZOOM_DYNAMIC);
+    assert_eq!(stab.params.read().adaptive_zoom_window, 0.0);
+    assert_eq!(stab.params.read().adaptive_zoom_method as i32, 0);
+
+    params.set_f64(Params::ZoomSpeed, 2.0).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomSpeed, true).unwrap();
+    assert_eq!(stab.params.read().adaptive_zoom_window, 2.0);
+    assert_eq!(stab.params.read().adaptive_zoom_method as i32, 0);
+
+    params.set_i32(Params::ZoomMethod, 1).unwrap();
+    instance.param_changed(&mut params, &base.manager_cache, Params::ZoomMethod, true).unwrap();
+    assert_eq!(stab.params.read().adaptive_zoom_method as i32, 1);
+}
@@ -5,11 +5,12 @@ mod frei0r;
 use frei0r::*;
 
 use cstr::cstr;
-use std::sync::{ Arc, atomic::AtomicBool };
+use std::ffi::CString;
+use std::sync::{ Arc, OnceLock, atomic::AtomicBool };
 
 use gyroflow_plugin_base::gyroflow_core::{ StabilizationManager, filesystem, stabilization::RGBA8, stabilization::Interpolation };
 use gyroflow_plugin_base::gyroflow_core::gpu::{ BufferDescription, Buffers, BufferSource };
-use gyroflow_plugin_base::GyroflowPluginBase;
+use gyroflow_plugin_base::{ GyroflowPluginBase, ClipInfo, Hosts, ParamDefinition, ParameterType, Params, PluginError, diagnostics, headless, lens_db, sequence, trim };
 
 #[derive(Default)]
 struct Instance {
@@ -19,8 +20,44 @@ struct Instance {
     // Params
     path: String,
     smoothness: f64,
+    /// Position offset in percent
+    position: (f64, f64),
+    /// Used part of the source media in seconds, the end is 0 until the end of the media
+    trim: (f64, f64),
     stab_overview: bool,
     time_scale: f64,
+    /// Why the project couldn't be loaded
+    load_error: Option<String>,
+}
+impl Instance {
+    fn trim_range_us(&self) -> Option<(i64, i64)> {
+        let end = if self.trim.1 > 0.0 { self.trim.1 } else { self.stab.params.read().duration_ms / 1000.0 };
+        (self.trim.0 > 0.0 || self.trim.1 > 0.0).then(|| ((self.trim.0 * 1_000_000.0).round() as i64, (end * 1_000_000.0).round() as i64))
+    }
+}
+
+struct ParamInfo {
+    def: &'static ParamDefinition,
+    name: CString,
+    explanation: CString,
+}
+
+// frei0r parameters are identified by index, in the order of the `Params` enum,
+// so parameters added later get new indices even when they're placed in the middle of the schema
+fn param_infos() -> &'static [ParamInfo] {
+    static PARAMS: OnceLock<Vec<ParamInfo>> = OnceLock::new();
+    PARAMS.get_or_init(|| {
+        let mut defs = ParamDefinition::all().iter().copied().filter(|x| x.is_available_in(Hosts::FREI0R)).collect::<Vec<_>>();
+        defs.sort_by_key(|x| x.param);
+        defs.into_iter().map(|def| ParamInfo {
+            def,
+            name:        CString::new(def.alias.unwrap_or(def.id)).unwrap(),
+            explanation: CString::new(def.label).unwrap(),
+        }).collect()
+    })
+}
+fn param_at(index: ::std::os::raw::c_int) -> Option<Params> {
+    param_infos().get(usize::try_from(index).ok()?).map(|x| x.def.param)
 }
 
 #[no_mangle] extern "C" fn f0r_init() -> ::std::os::raw::c_int { 1 }
@@ -36,41 +73,27 @@ extern "C" fn f0r_get_plugin_info(info: *mut f0r_plugin_info) {
         (*info).frei0r_version = FREI0R_MAJOR_VERSION;
         (*info).major_version = 0;
         (*info).minor_version = 1;
-        (*info).num_params = 4;
+        (*info).num_params = param_infos().len() as ::std::os::raw::c_int;
         (*info).explanation = cstr!("Gyroflow video stabilization").as_ptr();
     }
 }
 #[no_mangle]
 extern "C" fn f0r_get_param_info(info: *mut f0r_param_info, index: ::std::os::raw::c_int) {
+    let Some(param) = usize::try_from(index).ok().and_then(|x| param_infos().get(x)) else { return; };
     unsafe {
-        match index {
-            0 => {
-                (*info).name = cstr!("Project").as_ptr();
-                (*info).type_ = F0R_PARAM_STRING;
-                (*info).explanation = cstr!("Project file or video").as_ptr();
-            },
-            1 => {
-                (*info).name = cstr!("Smoothness").as_ptr();
-                (*info).type_ = F0R_PARAM_DOUBLE;
-                (*info).explanation = cstr!("Smoothness").as_ptr();
-            },
-            2 => {
-                (*info).name = cstr!("Overview").as_ptr();
-                (*info).type_ = F0R_PARAM_BOOL;
-                (*info).explanation = cstr!("Stabilization overview").as_ptr();
-            },
-            3 => {
-                (*info).name = cstr!("TimestampScale").as_ptr();
-                (*info).type_ = F0R_PARAM_DOUBLE;
-                (*info).explanation = cstr!("Scale for the input timestamp").as_ptr();
-            }
-            _ => { }
-        }
+        (*info).name = param.name.as_ptr();
+        (*info).explanation = param.explanation.as_ptr();
+        (*info).type_ = match param.def.typ {
+            ParameterType::Checkbox { .. } => F0R_PARAM_BOOL,
+            ParameterType::Slider   { .. } => F0R_PARAM_DOUBLE,
+            _ => F0R_PARAM_STRING,
+        };
     }
 }
 #[no_mangle]
 extern "C" fn f0r_construct(width: ::std::os::raw::c_uint, height: ::std::os::raw::c_uint) -> f0r_instance_t {
-    let stab = StabilizationManager::default();
+    let mut stab = StabilizationManager::default();
+    lens_db::share_with(&mut stab);
     {
         let mut stab = stab.stabilization.write();
         stab.share_wgpu_instances = true;
@@ -92,26 +115,42 @@ extern "C" fn f0r_set_param_value(instance: f0r_instance_t, param: f0r_param_t,
     if instance.is_null() { return; }
     let mut inst = unsafe { Box::from_raw(instance as *mut Instance) };
     unsafe {
-        match index {
-            0 => { // Project file
+        match param_at(index) {
+            Some(Params::ProjectPath) => { // Project file
                 let path = std::ffi::CStr::from_ptr(*(param as *mut *mut std::ffi::c_char)).to_string_lossy().to_owned()
                     .replace("_DRIVE_SEP_", ":/")
                     .replace("_DIR_SEP_", "/");
 
                 if path != inst.path {
                     inst.path = path.clone();
+                    inst.load_error = None;
+
+                    // Image sequences don't contain gyro data, so load their project instead
+                    let path = if path.ends_with(".gyroflow") || sequence::ImageSequence::parse(&path).is_none() {
+                        path
+                    } else {
+                        GyroflowPluginBase::get_project_path(&path).unwrap_or_else(|| sequence::media_path(&path))
+                    };
 
                     if !path.ends_with(".gyroflow") {
                         let url = filesystem::path_to_url(&path);
-                        if let Ok(mut file) = filesystem::open_file(&url, false, false) {
-                            let filesize = file.size;
-                            if let Err(e) = inst.stab.load_video_file(file.get_file(), filesize, &url, None, true) {
-                                log::error!("An error occured: {e:?}");
+                        match filesystem::open_file(&url, false, false) {
+                            Ok(mut file) => {
+                                let filesize = file.size;
+                                if let Err(e) = inst.stab.load_video_file(file.get_file(), filesize, &url, None, true) {
+                                    log::error!("An error occured: {e:?}");
+                                    inst.load_error = Some(PluginError::VideoUnreadable { path: path.clone(), reason: format!("{e:?}") }.to_string());
+                                }
+                            },
+                            Err(e) => {
+                                log::error!("Failed to open {url}: {e:?}");
+                                inst.load_error = Some(PluginError::ProjectNotFound(path.clone()).to_string());
                             }
-                        };
+                        }
                     } else {
                         if let Err(e) = inst.stab.import_gyroflow_file(&filesystem::path_to_url(&path), true, |_|(), Arc::new(AtomicBool::new(false)), true) {
                             log::error!("import_gyroflow_file error: {e:?}");
+                            inst.load_error = Some(PluginError::ProjectInvalid { path: path.clone(), reason: e.to_string() }.to_string());
                         }
                     }
 
@@ -122,12 +161,18 @@ extern "C" fn f0r_set_param_value(instance: f0r_instance_t, param: f0r_param_t,
                     let src_rect = GyroflowPluginBase::get_center_rect(inst.width, inst.height, org_ratio);
                     inst.stab.set_size(src_rect.2, src_rect.3);
                     inst.stab.set_output_size(inst.width, inst.height);
+                    trim::set_range(&inst.stab, inst.trim_range_us());
 
                     inst.stab.invalidate_smoothing();
                     inst.stab.recompute_blocking();
+
+                    // There's no status parameter in frei0r, so report the problems in the log
+                    for problem in diagnostics::check(&inst.stab, &ClipInfo { size: Some((inst.width, inst.height)), trim_ms: inst.trim_range_us().map(|(start, end)| (start as f64 / 1000.0, end as f64 / 1000.0)), ..Default::default() }) {
+                        problem.log();
+                    }
                 }
             },
-            1 => { // Smoothness
+            Some(Params::Smoothness) => { // Smoothness
                 let smoothness = *(param as *mut f64);
                 if (smoothness - inst.smoothness).abs() > 0.001 {
                     inst.smoothness = smoothness;
@@ -137,7 +182,29 @@ extern "C" fn f0r_set_param_value(instance: f0r_instance_t, param: f0r_param_t,
                     inst.stab.recompute_blocking();
                 }
             },
-            2 => { // Stabilization overview
+            Some(param_id @ (Params::PositionX | Params::PositionY)) => { // Position offset, -100% to 100%
+                let value = *(param as *mut f64);
+                let current = if param_id == Params::PositionX { &mut inst.position.0 } else { &mut inst.position.1 };
+                if (value - *current).abs() > 0.01 {
+                    *current = value;
+
+                    inst.stab.params.write().adaptive_zoom_center_offset = (inst.position.0 / 100.0, inst.position.1 / 100.0);
+                    inst.stab.invalidate_zooming();
+                    inst.stab.recompute_blocking();
+                }
+            },
+            Some(param_id @ (Params::TrimStart | Params::TrimEnd)) => { // Used part of the source media, in seconds
+                let value = *(param as *mut f64);
+                let current = if param_id == Params::TrimStart { &mut inst.trim.0 } else { &mut inst.trim.1 };
+                if (value - *current).abs() > 0.0001 {
+                    *current = value;
+
+                    if trim::set_range(&inst.stab, inst.trim_range_us()) {
+                        inst.stab.recompute_blocking();
+                    }
+                }
+            },
+            Some(Params::ToggleOverview) => { // Stabilization overview
                 let overview = *(param as *mut f64) > 0.5;
                 if overview != inst.stab_overview {
                     inst.stab_overview = overview;
@@ -145,7 +212,7 @@ extern "C" fn f0r_set_param_value(instance: f0r_instance_t, param: f0r_param_t,
                     inst.stab.recompute_undistortion();
                 }
             },
-            3 => { // Timestamp scale
+            Some(Params::TimestampScale) => { // Timestamp scale
                 inst.time_scale = *(param as *mut f64);
             },
             _ => { }
@@ -159,17 +226,29 @@ extern "C" fn f0r_get_param_value(instance: f0r_instance_t, param: f0r_param_t,
     if instance.is_null() { return; }
     let inst = unsafe { Box::from_raw(instance as *mut Instance) };
     unsafe {
-        match index {
-            0 => { // Project file
+        match param_at(index) {
+            Some(Params::ProjectPath) => { // Project file
                 *(param as *mut f0r_param_string) = std::ffi::CString::new(inst.path.clone()).unwrap().into_raw();
             },
-            1 => { // Smoothness
+            Some(Params::Smoothness) => { // Smoothness
                 *(param as *mut f64) = inst.smoothness;
             },
-            2 => { // Stabilization overview
+            Some(Params::PositionX) => { // Position offset X
+                *(param as *mut f64) = inst.position.0;
+            },
+            Some(Params::PositionY) => { // Position offset Y
+                *(param as *mut f64) = inst.position.1;
+            },
+            Some(Params::TrimStart) => { // Trim start
+                *(param as *mut f64) = inst.trim.0;
+            },
+            Some(Params::TrimEnd) => { // Trim end
+                *(param as *mut f64) = inst.trim.1;
+            },
+            Some(Params::ToggleOverview) => { // Stabilization overview
                 *(param as *mut f64) = if inst.stab_overview { 1.0 } else { 0.0 };
             },
-            3 => { // Timestamp scale
+            Some(Params::TimestampScale) => { // Timestamp scale
                 *(param as *mut f64) = inst.time_scale;
             },
             _ => { }
@@ -196,6 +275,16 @@ extern "C" fn f0r_update(instance: f0r_instance_t, time: f64, inframe: *const u3
     let inframe  = unsafe { std::slice::from_raw_parts_mut(inframe as *mut u8, inst.width * inst.height * 4) };
     let outframe = unsafe { std::slice::from_raw_parts_mut(outframe as *mut u8, inst.width * inst.height * 4) };
 
+    // frei0r can't report a failed render, so in headless mode the frame is either passed through or black
+    if let (Some(e), Some(mode)) = (&inst.load_error, headless::failure_mode()) {
+        match mode {
+            headless::FailureMode::PassThrough => { log::error!("Render failed at {time:.3}s, passing the frame through: {e}"); outframe.copy_from_slice(inframe); },
+            headless::FailureMode::Fail        => { log::error!("Render failed at {time:.3}s: {e}"); outframe.fill(0); },
+        }
+        let _ = Box::into_raw(inst);
+        return;
+    }
+
     let mut buffers = Buffers {
         input: BufferDescription {
             size: src_size,
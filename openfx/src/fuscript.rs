@@ -2,22 +2,13 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::SeqCst;
 use gyroflow_plugin_base::parking_lot::Mutex;
-use gyroflow_plugin_base::rfd;
+use gyroflow_plugin_base::{ Notification, Severity, notify };
 
 const FAILED_MSG: &str = "This feature relies on external scripting and is only available in paid Resolve Studio. You have to allow executing scripts:\n
 Set \"Preferences -> General -> External scripting using\" to \"Local\".\n\n
 It must be the currently displayed video on the timeline.\n
 It is also impossible to query file path on a compound clip.\n\nIn any case, you can just select the video or project file using the \"Browse\" button.";
 
-fn replace_frame_count(input: &str) -> String {
-    use regex::Regex;
-    let re = Regex::new(r"\[(\d+)-(\d+)\]").unwrap();
-
-    re.replace_all(input, |caps: &regex::Captures| {
-        format!("{}", &caps[1])
-    }).to_string()
-}
-
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub struct CurrentFileInfo {
@@ -77,7 +68,7 @@ impl CurrentFileInfo {
                     let duration_s = Self::parse_duration(lines[2], fps);
                     let par = lines[3];
                     let resolution = lines[4].split("x").filter_map(|x| x.parse::<usize>().ok()).collect::<Vec<_>>();
-                    let file_path = replace_frame_count(lines[5]);
+                    let file_path = gyroflow_plugin_base::sequence::media_path(lines[5]);
                     if fps > 0.0 && frame_count > 0 && duration_s > 0.0 && !file_path.is_empty() {
                         let info = Self {
                             file_path: file_path.to_string(),
@@ -101,11 +92,7 @@ impl CurrentFileInfo {
                 } else {
                     log::debug!("fuscript stdout: {stdout}");
                     log::debug!("fuscript stderr: {stderr}");
-                    rfd::MessageDialog::new()
-                        .set_title("Failed to query current video file path.")
-                        .set_description(FAILED_MSG)
-                        .set_level(rfd::MessageLevel::Warning)
-                        .show();
+                    notify(None, Notification::new(Severity::Warning, FAILED_MSG).with_title("Failed to query current video file path."));
                 }
             }
         });
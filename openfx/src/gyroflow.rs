@@ -1,11 +1,11 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{ Arc, Weak };
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::SeqCst;
 use ofx::*;
 use super::fuscript::*;
 use gyroflow_plugin_base::*;
 use gyroflow_plugin_base::parking_lot::{ Mutex, RwLock };
-use gyroflow_plugin_base::lru::LruCache;
 
 plugin_module!(
     "xyz.gyroflow",
@@ -27,68 +27,112 @@ pub fn frame_from_timetype(time: TimeType) -> f64 {
     }
 }
 
-define_params!(ParamHandler {
-    strings: [
-        Status              => status:           ParamHandle<String>,
-        InstanceId          => instance_id:      ParamHandle<String>,
-        ProjectData         => project_data:     ParamHandle<String>,
-        EmbeddedLensProfile => embedded_lens:    ParamHandle<String>,
-        EmbeddedPreset      => embedded_preset:  ParamHandle<String>,
-        ProjectPath         => project_path:     ParamHandle<String>,
-        OpenGyroflow        => open_in_gyroflow: ParamHandle<String>,
-        ReloadProject       => reload_project:   ParamHandle<String>,
-        OutputSizeSwap      => output_swap:      ParamHandle<String>,
-        OutputSizeToTimeline=> output_size_fit:  ParamHandle<String>,
-        LoadedProject       => loaded_project:   ParamHandle<String>,
-        LoadedPreset        => loaded_preset:    ParamHandle<String>,
-        LoadedLens          => loaded_lens:      ParamHandle<String>,
-    ],
-    bools: [
-        DisableStretch        => disable_stretch:         ParamHandle<bool>,
-        ToggleOverview        => toggle_overview:         ParamHandle<bool>,
-        DontDrawOutside       => dont_draw_outside:       ParamHandle<bool>,
-        IncludeProjectData    => include_project_data:    ParamHandle<bool>,
-        UseGyroflowsKeyframes => use_gyroflows_keyframes: ParamHandle<bool>,
-    ],
-    f64s: [
-        InputRotation         => input_rotation:           ParamHandle<Double>,
-        Fov                   => fov:                      ParamHandle<Double>,
-        Smoothness            => smoothness:               ParamHandle<Double>,
-        ZoomLimit             => zoom_limit:               ParamHandle<Double>,
-        LensCorrectionStrength=> lens_correction_strength: ParamHandle<Double>,
-        HorizonLockAmount     => horizon_lock_amount:      ParamHandle<Double>,
-        HorizonLockRoll       => horizon_lock_roll:        ParamHandle<Double>,
-        // PositionX             => positionx:                ParamHandle<Double>,
-        // PositionY             => positiony:                ParamHandle<Double>,
-        AdditionalYaw         => additional_yaw:           ParamHandle<Double>,
-        AdditionalPitch       => additional_pitch:         ParamHandle<Double>,
-        Rotation              => rotation:                 ParamHandle<Double>,
-        VideoSpeed            => video_speed:              ParamHandle<Double>,
-        OutputWidth           => output_width:             ParamHandle<Double>,
-        OutputHeight          => output_height:            ParamHandle<Double>,
-        //FusionStartFrame      => fusion_start_frame:       ParamHandle<Double>,
-    ],
-    i32s: [
-        Interpolation         => interpolation:            ParamHandle<Int>,
-        IntegrationMethod     => integration_method:       ParamHandle<Int>,
-    ],
-
-    get_string:  _s p    { Ok(p.get_value()?) },
-    set_string:  _s p, v { Ok(p.set_value(v.into())?) },
-    get_bool:    _s p    { Ok(p.get_value() ?) },
-    set_bool:    _s p, v { Ok(p.set_value(v)?) },
-    get_f64:     _s p    { Ok(p.get_value() ?) },
-    set_f64:     _s p, v { Ok(p.set_value(v)?) },
-    get_i32:     _s p    { Ok(p.get_value() ?) },
-    set_i32:     _s p, v { Ok(p.set_value(v)?) },
-    set_label:   _s p, l { Ok(p.set_label(l)?) },
-    set_hint:    _s p, h { Ok(p.set_hint(h) ?) },
-    set_enabled: _s p, e { Ok(p.set_enabled(e)?) },
-    get_bool_at_time: _s p, t    { Ok(p.get_value_at_time(frame_from_timetype(t))?) },
-    get_f64_at_time:  _s p, t    { Ok(p.get_value_at_time(frame_from_timetype(t))?) },
-    set_f64_at_time:  _s p, t, v { Ok(p.set_value_at_time(frame_from_timetype(t), v)?) },
-    is_keyframed: _s p { p.get_num_keys().unwrap_or_default() > 0 },
-    get_keyframes: _s p {
+enum ParamHandleType {
+    String(ParamHandle<String>),
+    Bool(ParamHandle<bool>),
+    Double(ParamHandle<Double>),
+    Int(ParamHandle<Int>),
+}
+
+macro_rules! any_handle {
+    ($handle:expr, $p:ident => $body:expr) => {
+        match $handle {
+            ParamHandleType::String($p) => $body,
+            ParamHandleType::Bool($p)   => $body,
+            ParamHandleType::Double($p) => $body,
+            ParamHandleType::Int($p)    => $body,
+        }
+    };
+}
+
+struct ParamHandler {
+    handles: HashMap<Params, ParamHandleType>,
+}
+
+impl ParamHandler {
+    fn new(param_set: &ParamSetHandle) -> Self {
+        let mut handles = HashMap::new();
+        for def in ParamDefinition::all() {
+            if !def.is_available_in(Hosts::OPENFX) { continue; }
+            let handle = match def.typ {
+                ParameterType::HiddenString | ParameterType::TextBox | ParameterType::Text |
+                ParameterType::Button          => param_set.parameter(def.id).map(ParamHandleType::String),
+                ParameterType::Slider   { .. } => param_set.parameter(def.id).map(ParamHandleType::Double),
+                ParameterType::Checkbox { .. } => param_set.parameter(def.id).map(ParamHandleType::Bool),
+                ParameterType::Select   { .. } |
+                ParameterType::DynamicSelect { .. } => param_set.parameter(def.id).map(ParamHandleType::Int),
+                ParameterType::Group    { .. } => continue,
+            };
+            match handle {
+                Ok(handle) => { handles.insert(def.param, handle); },
+                Err(e) => log::warn!("Parameter {} not available: {e:?}", def.id),
+            }
+        }
+        Self { handles }
+    }
+    fn handle(&self, param: Params) -> PluginResult<&ParamHandleType> {
+        self.handles.get(&param).ok_or_else(|| format!("Parameter {param:?} not available").into())
+    }
+    fn handle_mut(&mut self, param: Params) -> PluginResult<&mut ParamHandleType> {
+        self.handles.get_mut(&param).ok_or_else(|| format!("Parameter {param:?} not available").into())
+    }
+}
+
+fn wrong_type<T>(param: Params) -> PluginResult<T> {
+    Err(format!("Wrong parameter type: {param:?}").into())
+}
+
+impl GyroflowPluginParams for ParamHandler {
+    fn get_string(&self, param: Params) -> PluginResult<String> {
+        match self.handle(param)? { ParamHandleType::String(p) => Ok(p.get_value()?), _ => wrong_type(param) }
+    }
+    fn set_string(&mut self, param: Params, value: &str) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::String(p) => Ok(p.set_value(value.into())?), _ => wrong_type(param) }
+    }
+    fn get_bool(&self, param: Params) -> PluginResult<bool> {
+        match self.handle(param)? { ParamHandleType::Bool(p) => Ok(p.get_value()?), _ => wrong_type(param) }
+    }
+    fn set_bool(&mut self, param: Params, value: bool) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::Bool(p) => Ok(p.set_value(value)?), _ => wrong_type(param) }
+    }
+    fn get_f64(&self, param: Params) -> PluginResult<f64> {
+        match self.handle(param)? { ParamHandleType::Double(p) => Ok(p.get_value()?), _ => wrong_type(param) }
+    }
+    fn set_f64(&mut self, param: Params, value: f64) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::Double(p) => Ok(p.set_value(value)?), _ => wrong_type(param) }
+    }
+    fn get_i32(&self, param: Params) -> PluginResult<i32> {
+        match self.handle(param)? { ParamHandleType::Int(p) => Ok(p.get_value()?), _ => wrong_type(param) }
+    }
+    fn set_i32(&mut self, param: Params, value: i32) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::Int(p) => Ok(p.set_value(value)?), _ => wrong_type(param) }
+    }
+    fn set_label(&mut self, param: Params, label: &str) -> PluginResult<()> {
+        any_handle!(self.handle_mut(param)?, p => Ok(p.set_label(label)?))
+    }
+    fn set_hint(&mut self, param: Params, hint: &str) -> PluginResult<()> {
+        any_handle!(self.handle_mut(param)?, p => Ok(p.set_hint(hint)?))
+    }
+    fn set_enabled(&mut self, param: Params, enabled: bool) -> PluginResult<()> {
+        any_handle!(self.handle_mut(param)?, p => Ok(p.set_enabled(enabled)?))
+    }
+    fn get_bool_at_time(&self, param: Params, time: TimeType) -> PluginResult<bool> {
+        match self.handle(param)? { ParamHandleType::Bool(p) => Ok(p.get_value_at_time(frame_from_timetype(time))?), _ => wrong_type(param) }
+    }
+    fn get_f64_at_time(&self, param: Params, time: TimeType) -> PluginResult<f64> {
+        match self.handle(param)? { ParamHandleType::Double(p) => Ok(p.get_value_at_time(frame_from_timetype(time))?), _ => wrong_type(param) }
+    }
+    fn set_f64_at_time(&mut self, param: Params, time: TimeType, value: f64) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::Double(p) => Ok(p.set_value_at_time(frame_from_timetype(time), value)?), _ => wrong_type(param) }
+    }
+    fn is_keyframed(&self, param: Params) -> bool {
+        match self.handles.get(&param) {
+            Some(ParamHandleType::Double(p)) => p.get_num_keys().unwrap_or_default() > 0,
+            _ => false
+        }
+    }
+    fn get_keyframes(&self, param: Params) -> Vec<(TimeType, f64)> {
+        let Some(ParamHandleType::Double(p)) = self.handles.get(&param) else { return Vec::new(); };
         let num_keys = p.get_num_keys().unwrap_or_default();
         let mut ret = Vec::with_capacity(num_keys as usize);
         for i in 0..num_keys {
@@ -99,9 +143,11 @@ define_params!(ParamHandler {
             }
         }
         ret
-    },
-    clear_keyframes: _s p { Ok(p.delete_all_keys()?) },
-});
+    }
+    fn clear_keyframes(&mut self, param: Params) -> PluginResult<()> {
+        match self.handle_mut(param)? { ParamHandleType::Double(p) => Ok(p.delete_all_keys()?), _ => wrong_type(param) }
+    }
+}
 
 struct InstanceData {
     source_clip: ClipInstance,
@@ -112,13 +158,16 @@ struct InstanceData {
     supports_output_size: bool,
     is_fusion_page: bool,
     file_path: Option<String>,
+    /// Manager with the used range of a trimmed clip, instead of the trim ranges of its project
+    trimmed_manager: Weak<StabilizationManager>,
 
     current_file_info_pending: Arc<AtomicBool>,
     current_file_info: Arc<Mutex<Option<CurrentFileInfo>>>,
 }
 
 impl InstanceData {
-    fn stab_manager(&mut self, manager_cache: &Mutex<LruCache<String, Arc<StabilizationManager>>>, output_rect: RectI, loading_pending_video_file: bool) -> Result<Arc<StabilizationManager>> {
+    /// Returns `None` when the frame should be passed through, eg. while the project is loaded in the background
+    fn stab_manager(&mut self, manager_cache: &Mutex<ManagerCache>, output_rect: RectI, loading_pending_video_file: bool) -> Result<Option<Arc<StabilizationManager>>> {
         /*let source_rect = self.source_clip.get_region_of_definition(0.0)?;
         let mut source_rect = RectI {
             x1: source_rect.x1 as i32,
@@ -132,16 +181,21 @@ impl InstanceData {
         let in_size = ((source_rect.x2 - source_rect.x1) as usize, (source_rect.y2 - source_rect.y1) as usize);*/
         let out_size = ((output_rect.x2 - output_rect.x1) as usize, (output_rect.y2 - output_rect.y1) as usize);
 
-        self.plugin.stab_manager(&mut self.params, manager_cache, out_size, loading_pending_video_file).map_err(|e| {
-            log::error!("plugin.stab_manager error: {e:?}");
-            Error::UnknownError
-        })
+        match self.plugin.stab_manager(&mut self.params, manager_cache, out_size, loading_pending_video_file) {
+            Ok(stab) => Ok(Some(stab)),
+            Err(e) if self.plugin.pass_through_on_error(e.as_ref()) => Ok(None),
+            Err(e) => {
+                log::error!("plugin.stab_manager error: {e:?}");
+                Err(Error::UnknownError)
+            }
+        }
     }
     pub fn check_pending_file_info(&mut self) -> Result<bool> { // -> is_video_file
         if self.current_file_info_pending.load(SeqCst) {
             self.current_file_info_pending.store(false, SeqCst);
             let lock = self.current_file_info.lock();
             if let Some(ref current_file) = *lock {
+                self.plugin.media_file_path = current_file.file_path.clone();
                 if let Some(proj) = &current_file.project_path {
                     self.params.set_string(Params::ProjectPath, &proj).unwrap(); // TODO: unwrap
                 } else {
@@ -155,6 +209,57 @@ impl InstanceData {
     }
 }
 
+/// CPU image for `copy_pixels`, `data` points to the first pixel of `rect`
+struct CpuImage {
+    data: *mut u8,
+    rect: RectI,
+    stride: isize,
+    depth: BitDepth,
+}
+
+/// Copies the overlapping area of two RGBA images, converting the pixel depth. Returns false for unsupported images
+unsafe fn copy_pixels(src: &CpuImage, dst: &CpuImage) -> bool {
+    fn read(ptr: *const u8, depth: &BitDepth, i: usize) -> f32 {
+        unsafe { match depth {
+            BitDepth::Byte  => *ptr.add(i) as f32 / 255.0,
+            BitDepth::Short => (ptr as *const u16).add(i).read_unaligned() as f32 / 65535.0,
+            _               => (ptr as *const f32).add(i).read_unaligned()
+        } }
+    }
+    fn write(ptr: *mut u8, depth: &BitDepth, i: usize, v: f32) {
+        unsafe { match depth {
+            BitDepth::Byte  => *ptr.add(i) = (v.clamp(0.0, 1.0) * 255.0).round() as u8,
+            BitDepth::Short => (ptr as *mut u16).add(i).write_unaligned((v.clamp(0.0, 1.0) * 65535.0).round() as u16),
+            _               => (ptr as *mut f32).add(i).write_unaligned(v)
+        } }
+    }
+    let supported = |x: &CpuImage| matches!(x.depth, BitDepth::Byte | BitDepth::Short | BitDepth::Float) && !x.data.is_null();
+    if !supported(src) || !supported(dst) {
+        return false;
+    }
+    let (x1, x2) = (src.rect.x1.max(dst.rect.x1), src.rect.x2.min(dst.rect.x2));
+    let (y1, y2) = (src.rect.y1.max(dst.rect.y1), src.rect.y2.min(dst.rect.y2));
+    if x2 <= x1 {
+        return true;
+    }
+    let width = (x2 - x1) as usize;
+    let (src_bpp, dst_bpp) = (4 * src.depth.bits() / 8, 4 * dst.depth.bits() / 8);
+    for y in y1..y2 {
+        unsafe {
+            let s = src.data.offset((y - src.rect.y1) as isize * src.stride + ((x1 - src.rect.x1) as usize * src_bpp) as isize);
+            let d = dst.data.offset((y - dst.rect.y1) as isize * dst.stride + ((x1 - dst.rect.x1) as usize * dst_bpp) as isize);
+            if src.depth == dst.depth {
+                std::ptr::copy_nonoverlapping(s, d, width * src_bpp);
+            } else {
+                for i in 0..width * 4 {
+                    write(d, &dst.depth, i, read(s, &src.depth, i));
+                }
+            }
+        }
+    }
+    true
+}
+
 impl Execute for GyroflowPlugin {
     #[allow(clippy::float_cmp)]
     fn execute(&mut self, _plugin_context: &PluginContext, action: &mut Action) -> Result<Int> {
@@ -186,57 +291,77 @@ impl Execute for GyroflowPlugin {
 
                 let output_rect: RectI = output_image.get_region_of_definition()?;
 
-                let stab = instance_data.stab_manager(&self.gyroflow_plugin.manager_cache, output_rect, loading_pending_video_file)?;
+                // Exports wait for the project to be loaded, only the viewer gets pass-through frames
+                instance_data.plugin.set_interactive_render(in_args.get_interactive_render_status().unwrap_or_default());
+
+                let Some(stab) = instance_data.stab_manager(&self.gyroflow_plugin.manager_cache, output_rect, loading_pending_video_file)? else {
+                    // Pass-through until the project is loaded, or after a failure in headless mode
+                    let gpu = in_args.get_opengl_enabled().unwrap_or_default() || in_args.get_opencl_enabled().unwrap_or_default() ||
+                              in_args.get_metal_enabled().unwrap_or_default()  || in_args.get_cuda_enabled().unwrap_or_default();
+                    if gpu {
+                        // GPU images can't be copied here, so the render fails and the host renders the frame again later
+                        log::warn!("Unable to pass through a GPU frame at {time}");
+                        return FAILED;
+                    }
+                    let source_image = instance_data.source_clip.get_image(time)?;
+                    let src = CpuImage { data: source_image.get_data()? as *mut u8, rect: source_image.get_region_of_definition()?, stride: source_image.get_row_bytes()? as isize, depth: source_image.get_pixel_depth()? };
+                    let dst = CpuImage { data: output_image.get_data()? as *mut u8, rect: output_rect,                             stride: output_image.get_row_bytes()? as isize, depth: output_image.get_pixel_depth()? };
+                    if !unsafe { copy_pixels(&src, &dst) } {
+                        log::error!("Unable to pass through the frame at {time}, bits per component: {} -> {}", src.depth.bits(), dst.depth.bits());
+                        return FAILED;
+                    }
+                    return OK;
+                };
 
                 if !instance_data.supports_output_size {
-                    let _ = instance_data.params.output_width.set_enabled(false);
-                    let _ = instance_data.params.output_height.set_enabled(false);
-                    let _ = instance_data.params.output_swap.set_enabled(false);
-                    let _ = instance_data.params.output_size_fit.set_enabled(false);
+                    let _ = instance_data.params.set_enabled(Params::OutputWidth, false);
+                    let _ = instance_data.params.set_enabled(Params::OutputHeight, false);
+                    let _ = instance_data.params.set_enabled(Params::OutputSizeSwap, false);
+                    let _ = instance_data.params.set_enabled(Params::OutputSizeToTimeline, false);
                 }
                 /*if !instance_data.is_fusion_page {
-                    let _ = instance_data.params.fusion_start_frame.set_enabled(false);
+                    let _ = instance_data.params.set_enabled(Params::FusionStartFrame, false);
                 }*/
 
                 let params = stab.params.read();
                 let fps = params.fps;
+                let duration_ms = params.duration_ms;
                 let src_fps = instance_data.source_clip.get_frame_rate().unwrap_or(fps);
                 let org_ratio = params.size.0 as f64 / params.size.1 as f64;
-                let (has_accurate_timestamps, has_offsets) = {
-                    let gyro = stab.gyro.read();
-                    let md = gyro.file_metadata.read();
-                    (md.has_accurate_timestamps, !gyro.get_offsets().is_empty())
-                };
+
+                let sequence_first_frame = sequence::first_frame_of(&stab.input_file.read().url);
 
                 let mut speed_stretch = 1.0;
                 let mut time_adj = 0.0;
+                let mut used_frames = None;
                 if let Ok(range) = instance_data.source_clip.get_frame_range() {
                     if instance_data.is_fusion_page {
                         time_adj = range.min;
+                    } else if sequence_first_frame.is_some_and(|x| x as f64 == range.min) {
+                        // The host numbers the frames of the image sequence like its files
+                        time_adj = range.min;
                     }
                     if range.max > 0.0 && !instance_data.is_fusion_page {
-                        let duration_at_src_fps = (range.max / src_fps) * 1000.0;
+                        let duration_at_src_fps = ((range.max - time_adj) / src_fps) * 1000.0;
                         speed_stretch = ((params.duration_ms.round() / duration_at_src_fps.round()) * 100.0).floor() / 100.0;
                     }
+                    if range.max > range.min {
+                        // The frame range is inclusive
+                        used_frames = Some((range.min - time_adj, range.max + 1.0 - time_adj));
+                    }
                 }
 
                 // This should cover most cases by default, and for the rest users will use Fusion
                 if speed_stretch == 1.01 || speed_stretch == 0.99 || speed_stretch == 1.02 || speed_stretch == 0.98 || speed_stretch == 1.03 || speed_stretch == 0.97 {
                     speed_stretch = 1.0;
                 }
-
-                if (src_fps - fps).abs() > 0.01 {
-                    instance_data.plugin.set_status(&mut instance_data.params, "Timeline fps mismatch!", "Timeline frame rate doesn't match the clip frame rate! Use the plugin in Fusion instead", false);
-                } else if !has_accurate_timestamps && !has_offsets {
-                    instance_data.plugin.set_status(&mut instance_data.params, "Not synced. Open in Gyroflow", "Gyro data is not synced with the video, open the video in Gyroflow and add sync points (eg. by doing autosync)", false);
-                } else {
-                    instance_data.plugin.set_status(&mut instance_data.params, "OK", "OK", true);
-                }
+                let frame_to_us = |frame: f64| ((frame / src_fps * 1_000_000.0) * speed_stretch).round() as i64;
+                let trim_range_us = used_frames.map(|(start, end)| (frame_to_us(start), frame_to_us(end)));
 
                 let mut time = time;
                 //let time_adj = if instance_data.is_fusion_page { instance_data.params.fusion_start_frame.get_value().unwrap_or_default() } else { 0.0 };
                 time -= time_adj;
-                let mut timestamp_us = ((time / src_fps * 1_000_000.0) * speed_stretch).round() as i64;
+                let mut timestamp_us = frame_to_us(time);
 
                 // log::info!("fps: {fps:?}, src_fps: {src_fps:?}, speed_stretch: {speed_stretch:.6}, time: {time:?}, timestamp_us: {timestamp_us:?}");
 
@@ -251,6 +376,14 @@ impl Execute for GyroflowPlugin {
                 let source_timestamp_us = params.get_source_timestamp_at_ramped_timestamp(timestamp_us);
                 drop(params);
 
+                // Clips which aren't trimmed report the whole media, the trim ranges of the project are kept for them
+                // unless they were already replaced by the range of a trimmed clip
+                let host_range_us = trim_range_us.filter(|x| trim::is_sub_range(*x, duration_ms));
+                if host_range_us.is_some() || instance_data.trimmed_manager.ptr_eq(&Arc::downgrade(&stab)) {
+                    trim::set_range(&stab, host_range_us);
+                    instance_data.trimmed_manager = if host_range_us.is_some() { Arc::downgrade(&stab) } else { Weak::new() };
+                }
+
                 if source_timestamp_us != timestamp_us {
                     time = (source_timestamp_us as f64 / speed_stretch / 1_000_000.0 * src_fps).round();
                     timestamp_us = ((time / src_fps * 1_000_000.0) * speed_stretch).round() as i64;
@@ -277,6 +410,8 @@ impl Execute for GyroflowPlugin {
                 if src_size.2 <= 0 { src_size.2 = src_size.0 * 4 * 4 }; // assuming 32-bit float
                 if out_size.2 <= 0 { out_size.2 = out_size.0 * 4 * 4 }; // assuming 32-bit float
 
+                instance_data.plugin.update_status(&mut instance_data.params, &stab, &ClipInfo { fps: Some(src_fps), size: Some((src_size.0, src_size.1)), trim_ms: trim_range_us.map(|(start, end)| (start as f64 / 1000.0, end as f64 / 1000.0)) });
+
                 let src_rect = GyroflowPluginBase::get_center_rect(src_size.0, src_size.1, org_ratio);
 
                 let mut out_rect = if instance_data.params.get_bool_at_time(Params::DontDrawOutside, TimeType::Frame(time)).unwrap() { // TODO: unwrap
@@ -395,6 +530,7 @@ impl Execute for GyroflowPlugin {
                         output: BufferDescription { size: out_size, rect: out_rect,       data: buffers.1, rotation: None,           texture_copy: buffers.2 }
                     };
 
+                    ipc::report_frame(&stab, timestamp_us);
                     let processed = match output_image.get_pixel_depth()? {
                         BitDepth::None  => { return FAILED; },
                         BitDepth::Byte  => stab.process_pixels::<RGBA8>  (timestamp_us, None, &mut buffers),
@@ -408,7 +544,7 @@ impl Execute for GyroflowPlugin {
                             OK
                         },
                         Err(e) => {
-                            log::warn!("Failed to render: {e:?}");
+                            instance_data.plugin.set_error_status(&mut instance_data.params, &PluginError::from(e));
                             FAILED
                         }
                     }
@@ -430,50 +566,17 @@ impl Execute for GyroflowPlugin {
                     supports_output_size: true,
                     is_fusion_page: false,
                     file_path: None,
-                    params: ParamHandler {
-                        instance_id:              param_set.parameter("InstanceId")?,
-                        project_data:             param_set.parameter("ProjectData")?,
-                        embedded_lens:            param_set.parameter("EmbeddedLensProfile")?,
-                        embedded_preset:          param_set.parameter("EmbeddedPreset")?,
-                        project_path:             param_set.parameter("ProjectPath")?,
-                        disable_stretch:          param_set.parameter("DisableStretch")?,
-                        status:                   param_set.parameter("Status")?,
-                        open_in_gyroflow:         param_set.parameter("OpenGyroflow")?,
-                        reload_project:           param_set.parameter("ReloadProject")?,
-                        toggle_overview:          param_set.parameter("ToggleOverview")?,
-                        dont_draw_outside:        param_set.parameter("DontDrawOutside")?,
-                        include_project_data:     param_set.parameter("IncludeProjectData")?,
-                        input_rotation:           param_set.parameter("InputRotation")?,
-                        use_gyroflows_keyframes:  param_set.parameter("UseGyroflowsKeyframes")?,
-                        fov:                      param_set.parameter("Fov")?,
-                        smoothness:               param_set.parameter("Smoothness")?,
-                        zoom_limit:               param_set.parameter("ZoomLimit")?,
-                        lens_correction_strength: param_set.parameter("LensCorrectionStrength")?,
-                        horizon_lock_amount:      param_set.parameter("HorizonLockAmount")?,
-                        horizon_lock_roll:        param_set.parameter("HorizonLockRoll")?,
-                        video_speed:              param_set.parameter("VideoSpeed")?,
-                        //positionx:                param_set.parameter("PositionX")?,
-                        //positiony:                param_set.parameter("PositionY")?,
-                        additional_pitch:         param_set.parameter("AdditionalPitch")?,
-                        additional_yaw:           param_set.parameter("AdditionalYaw")?,
-                        rotation:                 param_set.parameter("Rotation")?,
-                        output_width:             param_set.parameter("OutputWidth")?,
-                        output_height:            param_set.parameter("OutputHeight")?,
-                        output_swap:              param_set.parameter("OutputSizeSwap")?,
-                        output_size_fit:          param_set.parameter("OutputSizeToTimeline")?,
-                        interpolation:            param_set.parameter("Interpolation")?,
-                        integration_method:       param_set.parameter("IntegrationMethod")?,
-
-                        loaded_project:           param_set.parameter("LoadedProject")?,
-                        loaded_lens:              param_set.parameter("LoadedLens")?,
-                        loaded_preset:            param_set.parameter("LoadedPreset")?,
-
-                        //fusion_start_frame:       param_set.parameter("FusionStartFrame")?,
-
-                        fields: Default::default(),
-                    },
+                    trimmed_manager: Weak::new(),
+                    params: ParamHandler::new(&param_set),
                     plugin: GyroflowPluginBaseInstance {
-                        managers:                    LruCache::new(std::num::NonZeroUsize::new(20).unwrap()),
+                        managers:                    ManagerCache::for_instance("Instance"),
+                        project_generation:          0,
+                        background_loading:          !headless::is_headless(),
+                        pending_load:                None,
+                        media_file_path:             String::new(),
+                        ipc_generation:              0,
+                        unsaved_changes:             false,
+                        preset_path:                 String::new(),
                         original_output_size:        (0, 0),
                         original_video_size:         (0, 0),
                         timeline_size:               (0, 0),
@@ -577,17 +680,18 @@ impl Execute for GyroflowPlugin {
 
                 let mut param_set = effect.parameter_set()?;
 
-                fn define_param(param_set: &mut ParamSetHandle, x: ParameterType, group: Option<&'static str>) -> Result<Int> {
-                    match x {
-                        ParameterType::HiddenString { id } => {
+                fn define_param(param_set: &mut ParamSetHandle, x: &ParamDefinition, group: Option<&'static str>) -> Result<Int> {
+                    if !x.is_available_in(Hosts::OPENFX) { return OK; }
+                    let (id, label, hint) = (x.id, x.label, x.hint);
+                    match x.typ {
+                        ParameterType::HiddenString => {
                             let mut param = param_set.param_define_string(id)?;
                             let _ = param.set_script_name(id);
                             param.set_secret(true)?;
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Button { id, label, hint } => {
-                            if id == "CreateCamera" { return OK; }
-                            if id == "LoadCurrent" && !CurrentFileInfo::is_available() {
+                        ParameterType::Button => {
+                            if x.param == Params::LoadCurrent && !CurrentFileInfo::is_available() {
                                 return OK;
                             }
                             let mut param = param_set.param_define_button(id)?;
@@ -596,7 +700,7 @@ impl Execute for GyroflowPlugin {
                             param.set_hint(hint)?;
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::TextBox { id, label, hint } => {
+                        ParameterType::TextBox => {
                             let mut param = param_set.param_define_string(id)?;
                             let _ = param.set_script_name(id);
                             param.set_string_type(ParamStringType::SingleLine)?;
@@ -604,7 +708,7 @@ impl Execute for GyroflowPlugin {
                             param.set_hint(hint)?;
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Text { id, label, hint } => {
+                        ParameterType::Text => {
                             let mut param = param_set.param_define_string(id)?;
                             param.set_string_type(ParamStringType::SingleLine)?;
                             param.set_label(label)?;
@@ -612,7 +716,7 @@ impl Execute for GyroflowPlugin {
                             //param.set_enabled(false)?;
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Slider { id, label, hint, min, max, default } => {
+                        ParameterType::Slider { min, max, default } => {
                             let mut param = param_set.param_define_double(id)?;
                             param.set_default(default)?;
                             param.set_display_min(min)?;
@@ -622,8 +726,7 @@ impl Execute for GyroflowPlugin {
                             let _ = param.set_script_name(id);
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Checkbox { id, label, hint, default } => {
-                            if id == "StabilizationSpeedRamp" { return OK; }
+                        ParameterType::Checkbox { default } => {
                             let mut param = param_set.param_define_boolean(id)?;
                             param.set_label(label)?;
                             param.set_hint(hint)?;
@@ -631,16 +734,17 @@ impl Execute for GyroflowPlugin {
                             let _ = param.set_script_name(id);
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Select { id, label, hint, options, default } => {
+                        ParameterType::Select { .. } | ParameterType::DynamicSelect { .. } => {
+                            let options = x.options();
                             let mut param = param_set.param_define_choice(id)?;
                             param.set_label(label)?;
                             param.set_hint(hint)?;
-                            param.set_default(options.iter().position(|x| *x == default).unwrap_or(0) as i32)?;
-                            param.set_choices(&options)?;
+                            param.set_default(x.default_index())?;
+                            param.set_choices(&options.iter().map(String::as_str).collect::<Vec<_>>())?;
                             let _ = param.set_script_name(id);
                             if let Some(group) = group { param.set_parent(group)?; }
                         }
-                        ParameterType::Group { id, label, parameters, opened } => {
+                        ParameterType::Group { parameters, opened, .. } => {
                             let mut param = param_set.param_define_group(id)?;
                             param.set_label(label)?;
                             param.set_group_open(opened)?;
@@ -664,7 +768,7 @@ impl Execute for GyroflowPlugin {
                         "ProjectGroup",
                         "AdjustGroup",
                         "KeyframesGroup",
-                        "ToggleOverview", "DontDrawOutside", "IncludeProjectData"
+                        "ToggleOverview", "DontDrawOutside", "IncludeProjectData", "WatchProjectFiles", "ExtractProjectData"
                     ])?;
 
                 OK